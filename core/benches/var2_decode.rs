@@ -0,0 +1,42 @@
+//! Compares decoding a table of many small rows as `Vec<Vec<u16>>` and as `Rows<u16>`.
+//!
+//! Run with `cargo bench -p pufu-core --bench var2_decode`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use pufu_core::{Config, Decode, Decoder, Encode, Encoder, Rows};
+
+const ROWS: u16 = 10_000;
+const ITERATIONS: u32 = 200;
+
+fn time(label: &str, mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iter = start.elapsed() / ITERATIONS;
+    println!("{label:<16} {per_iter:>12?} per decode");
+    per_iter
+}
+
+fn main() {
+    let table: Vec<Vec<u16>> = (0..ROWS)
+        .map(|row| (0..row % 4).map(|col| row ^ col).collect())
+        .collect();
+    let mut encoder = Encoder::new(Config::default());
+    table.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    println!("{ROWS} rows, {} payload bytes", out.len());
+    time("Vec<Vec<u16>>", || {
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        black_box(Vec::<Vec<u16>>::decode_field::<true>(&mut decoder).expect("decode"));
+    });
+    time("Rows<u16>", || {
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        black_box(Rows::<u16>::decode_field::<true>(&mut decoder).expect("decode"));
+    });
+}
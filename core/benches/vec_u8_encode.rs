@@ -0,0 +1,46 @@
+//! Compares encoding a byte vector through the bulk `Vec<u8>` copy and the per-element path.
+//!
+//! `Vec<&u8>` writes the same bytes but pushes them one at a time.
+//!
+//! Run with `cargo bench -p pufu-core --bench vec_u8_encode`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use pufu_core::{Config, Encode, Encoder};
+
+const BYTES: usize = 1 << 20;
+const ITERATIONS: u32 = 200;
+
+fn time(label: &str, mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iter = start.elapsed() / ITERATIONS;
+    println!("{label:<16} {per_iter:>12?} per encode");
+    per_iter
+}
+
+fn encode(value: &impl Encode) -> Vec<u8> {
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+    out
+}
+
+fn main() {
+    let bytes: Vec<u8> = (0..BYTES).map(|i| i as u8).collect();
+    let refs: Vec<&u8> = bytes.iter().collect();
+    assert_eq!(encode(&bytes), encode(&refs));
+
+    println!("{BYTES} bytes");
+    time("Vec<u8>", || {
+        black_box(encode(black_box(&bytes)));
+    });
+    time("Vec<&u8>", || {
+        black_box(encode(black_box(&refs)));
+    });
+}
@@ -1,18 +1,77 @@
 //! Codec error types for pufu payloads.
 
 /// Errors returned by encoding/decoding operations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CodecError {
     /// Input lengths or offsets do not match the expected layout.
     InvalidLength,
+    /// The input ends before a region it must contain.
+    Truncated {
+        /// Bytes required to read the region.
+        needed: usize,
+        /// Bytes actually available.
+        available: usize,
+    },
+    /// Header or VarEntry offsets are inconsistent with each other or with `total_len`.
+    MalformedOffsets,
+    /// A fixed-size array decoded a different number of elements than its length `N`.
+    ElementCountMismatch {
+        /// Element count required by the array type.
+        expected: usize,
+        /// Element count present in the input.
+        found: usize,
+    },
+    /// The payload is well-formed but fails a caller-requested check.
+    ValidationFailed,
+    /// A string segment is not valid UTF-8.
+    InvalidUtf8,
 }
 
 impl std::fmt::Display for CodecError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             CodecError::InvalidLength => write!(f, "invalid length"),
+            CodecError::Truncated { needed, available } => {
+                write!(
+                    f,
+                    "truncated input: needed {needed} bytes, {available} available"
+                )
+            }
+            CodecError::MalformedOffsets => write!(f, "malformed offsets"),
+            CodecError::ElementCountMismatch { expected, found } => {
+                write!(f, "expected {expected} array elements, found {found}")
+            }
+            CodecError::ValidationFailed => write!(f, "validation failed"),
+            CodecError::InvalidUtf8 => write!(f, "invalid utf-8"),
         }
     }
 }
 
 impl std::error::Error for CodecError {}
+
+#[cfg(test)]
+mod tests {
+    use super::CodecError;
+
+    #[test]
+    fn codec_errors_dedup_in_hash_set() {
+        let errors = [
+            CodecError::InvalidLength,
+            CodecError::ValidationFailed,
+            CodecError::InvalidLength,
+            CodecError::ElementCountMismatch {
+                expected: 3,
+                found: 2,
+            },
+            CodecError::ElementCountMismatch {
+                expected: 3,
+                found: 2,
+            },
+        ];
+        let set = errors
+            .iter()
+            .copied()
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(set.len(), 3);
+    }
+}
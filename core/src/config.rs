@@ -1,19 +1,144 @@
 //! Config for binary serialization protocol (see specs/0017-config.md).
 
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::layout::{COMPACT_HEADER_LEN, FIXED_OFFSET_LEN, HEADER_LEN};
 use crate::Endian;
 
 /// Default magic bytes (e.g. b"svsd").
 pub const DEFAULT_MAGIC: [u8; 4] = [0x73, 0x76, 0x73, 0x64];
 
+/// Longest magic `Magic` stores inline; longer owned magics are kept on the heap.
+const INLINE_MAGIC_LEN: usize = 8;
+
+/// Magic bytes of a `Config`, dereferencing to `[u8]`.
+///
+/// `&'static [u8]` magics stay borrowed and owned magics of up to eight bytes are stored inline,
+/// so cloning a `Config` on nested encode/decode paths does not allocate. Only owned magics
+/// longer than that are heap-allocated.
+#[derive(Clone)]
+pub struct Magic(MagicRepr);
+
+#[derive(Clone)]
+enum MagicRepr {
+    Static(&'static [u8]),
+    Inline {
+        bytes: [u8; INLINE_MAGIC_LEN],
+        len: u8,
+    },
+    Heap(Vec<u8>),
+}
+
+impl Magic {
+    /// Copies `magic` inline when it fits, else onto the heap.
+    fn owned(magic: &[u8]) -> Self {
+        match Self::inline(magic) {
+            Some(inline) => inline,
+            None => Self(MagicRepr::Heap(magic.to_vec())),
+        }
+    }
+
+    fn inline(magic: &[u8]) -> Option<Self> {
+        let mut bytes = [0; INLINE_MAGIC_LEN];
+        bytes.get_mut(..magic.len())?.copy_from_slice(magic);
+        Some(Self(MagicRepr::Inline {
+            bytes,
+            len: magic.len() as u8,
+        }))
+    }
+}
+
+impl Default for Magic {
+    /// Returns `DEFAULT_MAGIC`, borrowed.
+    fn default() -> Self {
+        Self(MagicRepr::Static(&DEFAULT_MAGIC))
+    }
+}
+
+impl Deref for Magic {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.0 {
+            MagicRepr::Static(bytes) => bytes,
+            MagicRepr::Inline { bytes, len } => &bytes[..*len as usize],
+            MagicRepr::Heap(bytes) => bytes,
+        }
+    }
+}
+
+impl AsRef<[u8]> for Magic {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl PartialEq for Magic {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Magic {}
+
+impl fmt::Debug for Magic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Magic {
+    fn from(magic: [u8; N]) -> Self {
+        Self::owned(&magic)
+    }
+}
+
+impl From<&'static [u8]> for Magic {
+    fn from(magic: &'static [u8]) -> Self {
+        Self(MagicRepr::Static(magic))
+    }
+}
+
+impl From<Vec<u8>> for Magic {
+    fn from(magic: Vec<u8>) -> Self {
+        Self::inline(&magic).unwrap_or(Self(MagicRepr::Heap(magic)))
+    }
+}
+
+impl From<Cow<'static, [u8]>> for Magic {
+    fn from(magic: Cow<'static, [u8]>) -> Self {
+        match magic {
+            Cow::Borrowed(magic) => magic.into(),
+            Cow::Owned(magic) => magic.into(),
+        }
+    }
+}
+
 /// Config carrying magic, version, and endianness for encode/decode.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
-    /// Four-byte magic identifier at the start of the payload (serialized).
-    pub magic: [u8; 4],
+    /// Magic identifier at the start of the payload (serialized); four bytes by default.
+    ///
+    /// Cloning it allocates only for owned magics longer than eight bytes; see `Magic`.
+    pub magic: Magic,
     /// Protocol version byte (serialized).
     pub version: u8,
     /// Byte order for multi-byte integer fields. Not serialized; used only at encode/decode time.
     pub endian: Endian,
+    /// Maximum element count accepted per decoded fixed-width vector segment. Not serialized.
+    pub max_elements: Option<usize>,
+    /// Alignment of the FixedRegion start within the buffer `Encoder::finalize` writes into, so a
+    /// magic+version prefix or bytes already in `out` are accounted for. `0` and `1` disable
+    /// padding. When enabled, the header gains a `fixed_offset` field recording where the
+    /// FixedRegion starts, followed by zero padding; the decoder reads that field instead of
+    /// recomputing the padding. Not serialized; both sides must agree on whether it is enabled.
+    pub fixed_align: u32,
+    /// Omit `total_len` from the header and take it from the buffer length instead. Saves four
+    /// bytes per payload, but payloads can no longer carry a trailer or be framed by
+    /// `StreamDecoder`. Not serialized; both sides must agree.
+    pub compact_header: bool,
 }
 
 impl Config {
@@ -21,14 +146,52 @@ impl Config {
     pub fn builder() -> ConfigBuilder {
         ConfigBuilder::default()
     }
+
+    /// Returns a builder seeded with this config's values, to override individual fields.
+    pub fn to_builder(&self) -> ConfigBuilder {
+        ConfigBuilder::from(self)
+    }
+
+    /// Returns a default config using the host byte order, resolved to `Little` or `Big`.
+    ///
+    /// Storing the resolved order keeps the config meaningful if it is logged or shared; the
+    /// payload itself is still only portable to hosts with the same byte order.
+    pub fn native() -> Self {
+        Config::builder()
+            .endian(Endian::Native.resolve_native())
+            .build()
+    }
+
+    /// Returns the header length: `COMPACT_HEADER_LEN` in compact mode, else `HEADER_LEN`, plus
+    /// `FIXED_OFFSET_LEN` when `fixed_align > 1`. A payload with a trailer adds `TRAILER_LEN_SIZE`.
+    pub fn header_len(&self) -> u32 {
+        let len = if self.compact_header {
+            COMPACT_HEADER_LEN
+        } else {
+            HEADER_LEN
+        };
+        if self.records_fixed_offset() {
+            len + FIXED_OFFSET_LEN
+        } else {
+            len
+        }
+    }
+
+    /// Returns `true` if the header carries a `fixed_offset` field (`fixed_align > 1`).
+    pub(crate) fn records_fixed_offset(&self) -> bool {
+        self.fixed_align > 1
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            magic: DEFAULT_MAGIC,
+            magic: Magic::default(),
             version: 1,
             endian: Endian::Little,
+            max_elements: None,
+            fixed_align: 1,
+            compact_header: false,
         }
     }
 }
@@ -36,15 +199,37 @@ impl Default for Config {
 /// Builder for Config.
 #[derive(Debug, Clone, Default)]
 pub struct ConfigBuilder {
-    magic: Option<[u8; 4]>,
+    magic: Option<Magic>,
     version: Option<u8>,
     endian: Option<Endian>,
+    max_elements: Option<usize>,
+    fixed_align: Option<u32>,
+    compact_header: bool,
+}
+
+impl From<&Config> for ConfigBuilder {
+    fn from(config: &Config) -> Self {
+        Self {
+            magic: Some(config.magic.clone()),
+            version: Some(config.version),
+            endian: Some(config.endian),
+            max_elements: config.max_elements,
+            fixed_align: Some(config.fixed_align),
+            compact_header: config.compact_header,
+        }
+    }
 }
 
 impl ConfigBuilder {
-    /// Sets the magic bytes.
-    pub fn magic(mut self, magic: [u8; 4]) -> Self {
-        self.magic = Some(magic);
+    /// Sets a four-byte magic, stored inline.
+    pub fn magic(self, magic: [u8; 4]) -> Self {
+        self.magic_bytes(magic)
+    }
+
+    /// Sets a magic of any length, e.g. two or eight bytes. A `&'static [u8]` is kept borrowed
+    /// and owned magics of up to eight bytes are stored inline.
+    pub fn magic_bytes(mut self, magic: impl Into<Magic>) -> Self {
+        self.magic = Some(magic.into());
         self
     }
 
@@ -54,6 +239,12 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the four-byte magic and the version from a combined five-byte tag, magic first.
+    pub fn tag(self, tag: [u8; 5]) -> Self {
+        let [m0, m1, m2, m3, version] = tag;
+        self.magic([m0, m1, m2, m3]).version(version)
+    }
+
     /// Sets the endianness.
     pub fn endian(mut self, endian: Endian) -> Self {
         self.endian = Some(endian);
@@ -70,17 +261,108 @@ impl ConfigBuilder {
         self.endian(Endian::Little)
     }
 
-    /// Sets endianness to the platform's native byte order.
+    /// Sets endianness to the platform's native byte order (see `Endian::Native` for caveats).
     pub fn native(self) -> Self {
         self.endian(Endian::Native)
     }
 
+    /// Limits how many elements a single decoded vector segment may hold.
+    pub fn max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = Some(max_elements);
+        self
+    }
+
+    /// Pads after the header so the FixedRegion starts at a multiple of `align` bytes within the
+    /// written buffer; see `Config::fixed_align`.
+    pub fn fixed_align(mut self, align: u32) -> Self {
+        self.fixed_align = Some(align);
+        self
+    }
+
+    /// Drops `total_len` from the header; see `Config::compact_header` for the tradeoff.
+    pub fn compact_header(mut self, compact: bool) -> Self {
+        self.compact_header = compact;
+        self
+    }
+
     /// Builds a Config; missing fields use defaults (DEFAULT_MAGIC, version 1, Little).
     pub fn build(self) -> Config {
         Config {
-            magic: self.magic.unwrap_or(DEFAULT_MAGIC),
+            magic: self.magic.unwrap_or_default(),
             version: self.version.unwrap_or(1),
             endian: self.endian.unwrap_or(Endian::Little),
+            max_elements: self.max_elements,
+            fixed_align: self.fixed_align.unwrap_or(1),
+            compact_header: self.compact_header,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, MagicRepr};
+    use crate::Endian;
+
+    #[test]
+    fn to_builder_overrides_single_field() {
+        let base = Config::builder()
+            .magic(*b"base")
+            .version(7)
+            .max_elements(16)
+            .fixed_align(16)
+            .compact_header(true)
+            .build();
+        let big = base.to_builder().big().build();
+
+        assert_eq!(big.endian, Endian::Big);
+        assert_eq!(
+            Config {
+                endian: Endian::Little,
+                ..big.clone()
+            },
+            base
+        );
+        assert_eq!(Config::default().to_builder().build(), Config::default());
+    }
+
+    #[test]
+    fn default_and_static_magic_stay_borrowed() {
+        assert!(matches!(Config::default().magic.0, MagicRepr::Static(_)));
+        let config = Config::builder().magic_bytes(&b"PUFU\r\n"[..]).build();
+        assert!(matches!(
+            config.clone().magic.0,
+            MagicRepr::Static(b"PUFU\r\n")
+        ));
+    }
+
+    #[test]
+    fn short_owned_magic_is_stored_inline() {
+        let config = Config::builder().magic(*b"PUFU").build();
+        assert!(matches!(config.clone().magic.0, MagicRepr::Inline { .. }));
+        assert_eq!(*config.magic, *b"PUFU");
+
+        let eight = Config::builder()
+            .magic_bytes(b"PUFU\r\n\x1a\n".to_vec())
+            .build();
+        assert!(matches!(eight.magic.0, MagicRepr::Inline { .. }));
+        assert_eq!(*eight.magic, *b"PUFU\r\n\x1a\n");
+
+        let long = Config::builder().magic_bytes(b"PUFU-LONG".to_vec()).build();
+        assert!(matches!(long.magic.0, MagicRepr::Heap(_)));
+        assert_eq!(
+            long.magic,
+            Config::builder()
+                .magic_bytes(&b"PUFU-LONG"[..])
+                .build()
+                .magic
+        );
+    }
+
+    #[test]
+    fn tag_sets_magic_and_version() {
+        let config = Config::builder().tag(*b"PUFU\x07").build();
+        assert_eq!(*config.magic, *b"PUFU");
+        assert_eq!(config.version, 7);
+        assert_eq!(config, Config::builder().magic(*b"PUFU").version(7).build());
+    }
+}
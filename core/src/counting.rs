@@ -0,0 +1,87 @@
+//! Size-only encoding pass behind `encoded_len`.
+
+use crate::layout::{fixed_region_offset_after, VAR_ENTRY_SIZE};
+use crate::{CodecError, Config, DataType, Encoder};
+
+/// Tallies region lengths the way `Encoder` fills its regions, without writing any bytes.
+///
+/// Fields are counted through `Encode::measure_field`; `encoded_len` then reports what
+/// `Encoder::finalize` would write into an empty buffer.
+#[derive(Debug, Clone)]
+pub struct CountingEncoder {
+    /// Config the counted payload would be written with.
+    config: Config,
+    /// FixedRegion bytes counted so far.
+    fixed_len: usize,
+    /// Var segments counted so far.
+    var_count: usize,
+    /// Data region bytes counted so far.
+    data_len: usize,
+}
+
+impl CountingEncoder {
+    /// Creates a CountingEncoder with the given Config and nothing counted.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            fixed_len: 0,
+            var_count: 0,
+            data_len: 0,
+        }
+    }
+
+    /// Returns a reference to the Config (e.g. for nested payloads).
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Counts `len` bytes written to the FixedRegion.
+    pub fn add_fixed(&mut self, len: usize) {
+        self.fixed_len = self.fixed_len.saturating_add(len);
+    }
+
+    /// Counts one var segment of `len` data bytes.
+    pub fn add_var(&mut self, len: usize) {
+        self.var_count = self.var_count.saturating_add(1);
+        self.data_len = self.data_len.saturating_add(len);
+    }
+
+    /// Counts one var segment of `count` fixed-width `T` elements, like `Vec<T>`.
+    pub fn add_fixed_segment<T: DataType>(&mut self, count: usize) {
+        if T::LENGTH == 0 && count != 0 {
+            panic!("vectors of zero-length fixed elements must be empty");
+        }
+        self.add_var(count.saturating_mul(T::LENGTH));
+    }
+
+    /// Counts a nested payload holding the fields counted by `nested` as one var segment.
+    pub fn add_nested(&mut self, nested: &CountingEncoder) {
+        // An oversized nested payload cannot be written, so make the total overflow too.
+        self.add_var(nested.encoded_len().unwrap_or(usize::MAX));
+    }
+
+    /// Counts the regions `encoder` has already filled, for types without a size-only path.
+    pub fn add_encoder(&mut self, encoder: &Encoder) {
+        self.add_fixed(encoder.fixed.len());
+        self.var_count = self.var_count.saturating_add(encoder.var_length.len());
+        self.data_len = self.data_len.saturating_add(encoder.data.len());
+    }
+
+    /// Returns the number of bytes `Encoder::finalize` would append to an empty buffer.
+    ///
+    /// Fails with `CodecError::InvalidLength` when the payload would not fit `u32` offsets.
+    pub fn encoded_len(&self) -> Result<usize, CodecError> {
+        let fixed_offset =
+            fixed_region_offset_after(self.config.header_len(), self.config.fixed_align, 0)
+                .ok_or(CodecError::InvalidLength)?;
+        let total_len = self
+            .var_count
+            .checked_mul(VAR_ENTRY_SIZE as usize)
+            .and_then(|n| n.checked_add(fixed_offset as usize))
+            .and_then(|n| n.checked_add(self.fixed_len))
+            .and_then(|n| n.checked_add(self.data_len))
+            .ok_or(CodecError::InvalidLength)?;
+        u32::try_from(total_len).map_err(|_| CodecError::InvalidLength)?;
+        Ok(total_len)
+    }
+}
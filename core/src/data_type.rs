@@ -1,6 +1,6 @@
 //! Data type descriptors for pufu encoding.
 
-use crate::Endian;
+use crate::{Config, CountingEncoder, Encode, Encoder, Endian};
 
 /// Describes how a type is encoded in the payload.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,6 +11,17 @@ pub enum DataMode {
     Var1,
 }
 
+/// Byte length of `n` fixed elements of `elem` bytes each.
+///
+/// Only evaluated in const context, where overflow fails compilation either way; the explicit
+/// panic names the cause in that error.
+pub(crate) const fn array_length(elem: usize, n: usize) -> usize {
+    match elem.checked_mul(n) {
+        Some(len) => len,
+        None => panic!("fixed array byte length overflows usize"),
+    }
+}
+
 /// Defines how a type contributes fixed or variable data to an encoder.
 pub trait DataType {
     /// Encoding mode for this type.
@@ -24,11 +35,81 @@ pub trait DataType {
         panic!("push_fixed_data called for non-fixed data type");
     }
 
+    /// Push the fixed-width bytes of every item in `items`, in order.
+    ///
+    /// Types whose in-memory bytes are already the wire bytes override this with a bulk copy.
+    fn push_fixed_slice(items: &[Self], out: &mut Vec<u8>, endian: &Endian)
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.push_fixed_data(out, endian);
+        }
+    }
+
     /// Push variable-length bytes into the data region and record length.
     fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
         let _ = (var_length, data, endian);
         panic!("push_var1_data called for fixed data type");
     }
+
+    /// Push this value as one var entry of an outer vector, with the encoder's full config.
+    ///
+    /// Defaults to `push_var1_data`. Vectors of vectors override it to write themselves as a
+    /// nested payload, which needs the config to lay out the inner header.
+    fn push_var1_entry(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, config: &Config) {
+        self.push_var1_data(var_length, data, &config.endian);
+    }
+
+    /// Count the var entries `push_var1_entry` would push, without writing their bytes.
+    ///
+    /// The default pushes into scratch buffers and counts what was written.
+    fn measure_var1_entry(&self, counter: &mut CountingEncoder) {
+        let (mut var_length, mut data) = (Vec::new(), Vec::new());
+        self.push_var1_entry(&mut var_length, &mut data, counter.config());
+        for len in var_length {
+            counter.add_var(len as usize);
+        }
+    }
+}
+
+/// Writes `items` as a nested payload holding them as its last var field, in a single var entry.
+fn push_nested_entry<T: DataType>(
+    items: &[T],
+    var_length: &mut Vec<u32>,
+    data: &mut Vec<u8>,
+    config: &Config,
+) {
+    let mut encoder = Encoder::new(config.clone());
+    items.encode_field::<true>(&mut encoder);
+    let start = data.len();
+    encoder.finalize(data).expect("finalize");
+    var_length.push((data.len() - start) as u32);
+}
+
+impl DataType for u8 {
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = 1;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        let _ = endian;
+        encoder_fixed.push(*self);
+    }
+
+    fn push_fixed_slice(items: &[Self], out: &mut Vec<u8>, endian: &Endian) {
+        let _ = endian;
+        out.extend_from_slice(items);
+    }
+}
+
+impl DataType for bool {
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = 1;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        let _ = endian;
+        encoder_fixed.push(u8::from(*self));
+    }
 }
 
 macro_rules! impl_fixed_data_type_for_primitive {
@@ -42,7 +123,7 @@ macro_rules! impl_fixed_data_type_for_primitive {
                     match endian {
                         Endian::Little => encoder_fixed.extend_from_slice(&self.to_le_bytes()),
                         Endian::Big => encoder_fixed.extend_from_slice(&self.to_be_bytes()),
-                        Endian::Native => encoder_fixed.extend_from_slice(&self.to_le_bytes()),
+                        Endian::Native => encoder_fixed.extend_from_slice(&self.to_ne_bytes()),
                     }
                 }
             }
@@ -51,22 +132,41 @@ macro_rules! impl_fixed_data_type_for_primitive {
     };
 }
 
-impl_fixed_data_type_for_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_fixed_data_type_for_primitive!(u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// References to scalars encode like the scalar itself, so `Vec<&u32>` and `&[&u32]` can be
+/// encoded without cloning into an owned collection.
+macro_rules! impl_fixed_data_type_for_scalar_ref {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl DataType for &$t {
+                const MODE: DataMode = DataMode::Fixed;
+                const LENGTH: usize = <$t as DataType>::LENGTH;
+
+                fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+                    (**self).push_fixed_data(encoder_fixed, endian);
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_data_type_for_scalar_ref!(
+    u8, bool, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
 
 impl<T, const N: usize> DataType for [T; N]
 where
     T: DataType,
 {
     const MODE: DataMode = DataMode::Fixed;
-    const LENGTH: usize = T::LENGTH * N;
+    const LENGTH: usize = array_length(T::LENGTH, N);
 
     fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
         if T::MODE != DataMode::Fixed {
             panic!("fixed arrays require fixed data types");
         }
-        for i in self {
-            i.push_fixed_data(encoder_fixed, endian);
-        }
+        T::push_fixed_slice(&self[..], encoder_fixed, endian);
     }
 }
 
@@ -75,15 +175,13 @@ where
     T: DataType,
 {
     const MODE: DataMode = DataMode::Fixed;
-    const LENGTH: usize = T::LENGTH * N;
+    const LENGTH: usize = array_length(T::LENGTH, N);
 
     fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
         if T::MODE != DataMode::Fixed {
             panic!("fixed arrays require fixed data types");
         }
-        for i in self.iter() {
-            i.push_fixed_data(encoder_fixed, endian);
-        }
+        T::push_fixed_slice(&self[..], encoder_fixed, endian);
     }
 }
 
@@ -92,15 +190,13 @@ where
     T: DataType,
 {
     const MODE: DataMode = DataMode::Fixed;
-    const LENGTH: usize = T::LENGTH * N;
+    const LENGTH: usize = array_length(T::LENGTH, N);
 
     fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
         if T::MODE != DataMode::Fixed {
             panic!("fixed arrays require fixed data types");
         }
-        for i in self.iter() {
-            i.push_fixed_data(encoder_fixed, endian);
-        }
+        T::push_fixed_slice(&self[..], encoder_fixed, endian);
     }
 }
 
@@ -132,6 +228,71 @@ where
     }
 }
 
+impl<T0, T1, T2> DataType for (T0, T1, T2)
+where
+    T0: DataType,
+    T1: DataType,
+    T2: DataType,
+{
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = T0::LENGTH + T1::LENGTH + T2::LENGTH;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        if T0::MODE != DataMode::Fixed || T1::MODE != DataMode::Fixed || T2::MODE != DataMode::Fixed
+        {
+            panic!("fixed tuples require fixed data types");
+        }
+        let (t0, t1, t2) = self;
+        t0.push_fixed_data(encoder_fixed, endian);
+        t1.push_fixed_data(encoder_fixed, endian);
+        t2.push_fixed_data(encoder_fixed, endian);
+    }
+
+    fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
+        if T0::MODE != DataMode::Fixed || T1::MODE != DataMode::Fixed || T2::MODE != DataMode::Fixed
+        {
+            panic!("fixed tuples require fixed data types");
+        }
+        let (t0, t1, t2) = self;
+        t0.push_fixed_data(data, endian);
+        t1.push_fixed_data(data, endian);
+        t2.push_fixed_data(data, endian);
+        var_length.push((T0::LENGTH + T1::LENGTH + T2::LENGTH) as u32);
+    }
+}
+
+impl<T> DataType for std::num::Wrapping<T>
+where
+    T: DataType,
+{
+    const MODE: DataMode = T::MODE;
+    const LENGTH: usize = T::LENGTH;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        self.0.push_fixed_data(encoder_fixed, endian);
+    }
+
+    fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
+        self.0.push_var1_data(var_length, data, endian);
+    }
+}
+
+impl<T> DataType for std::cmp::Reverse<T>
+where
+    T: DataType,
+{
+    const MODE: DataMode = T::MODE;
+    const LENGTH: usize = T::LENGTH;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        self.0.push_fixed_data(encoder_fixed, endian);
+    }
+
+    fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
+        self.0.push_var1_data(var_length, data, endian);
+    }
+}
+
 impl<T> DataType for Vec<T>
 where
     T: DataType,
@@ -145,6 +306,16 @@ where
         let this: &[T] = self;
         this.push_var1_data(var_length, data, endian);
     }
+
+    fn push_var1_entry(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, config: &Config) {
+        let this: &[T] = self;
+        this.push_var1_entry(var_length, data, config);
+    }
+
+    fn measure_var1_entry(&self, counter: &mut CountingEncoder) {
+        let this: &[T] = self;
+        this.measure_var1_entry(counter);
+    }
 }
 
 impl<T> DataType for &Vec<T>
@@ -160,6 +331,16 @@ where
         let this: &[T] = self.as_slice();
         this.push_var1_data(var_length, data, endian);
     }
+
+    fn push_var1_entry(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, config: &Config) {
+        let this: &[T] = self.as_slice();
+        this.push_var1_entry(var_length, data, config);
+    }
+
+    fn measure_var1_entry(&self, counter: &mut CountingEncoder) {
+        let this: &[T] = self.as_slice();
+        this.measure_var1_entry(counter);
+    }
 }
 
 impl<T> DataType for &mut Vec<T>
@@ -175,6 +356,16 @@ where
         let this: &[T] = self.as_slice();
         this.push_var1_data(var_length, data, endian);
     }
+
+    fn push_var1_entry(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, config: &Config) {
+        let this: &[T] = self.as_slice();
+        this.push_var1_entry(var_length, data, config);
+    }
+
+    fn measure_var1_entry(&self, counter: &mut CountingEncoder) {
+        let this: &[T] = self.as_slice();
+        this.measure_var1_entry(counter);
+    }
 }
 
 impl<T> DataType for &[T]
@@ -187,14 +378,28 @@ where
         if T::MODE != DataMode::Fixed {
             panic!("var1 slices require fixed element types");
         }
-        let mut length = 0;
+        T::push_fixed_slice(self, data, endian);
+        var_length.push((self.len() * T::LENGTH) as u32);
+    }
 
-        for item in self.iter() {
-            item.push_fixed_data(data, endian);
-            length += T::LENGTH;
+    /// Var1 elements (e.g. the rows of a `Vec<Vec<T>>`) cannot be flattened into one entry, so
+    /// the slice is written as a nested payload instead.
+    fn push_var1_entry(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, config: &Config) {
+        match T::MODE {
+            DataMode::Fixed => self.push_var1_data(var_length, data, &config.endian),
+            DataMode::Var1 => push_nested_entry(self, var_length, data, config),
         }
+    }
 
-        var_length.push(length as u32);
+    fn measure_var1_entry(&self, counter: &mut CountingEncoder) {
+        match T::MODE {
+            DataMode::Fixed => counter.add_var(self.len().saturating_mul(T::LENGTH)),
+            DataMode::Var1 => {
+                let mut nested = CountingEncoder::new(counter.config().clone());
+                self.measure_field::<true>(&mut nested);
+                counter.add_nested(&nested);
+            }
+        }
     }
 }
 
@@ -211,4 +416,87 @@ where
         let this: &[T] = self;
         this.push_var1_data(var_length, data, endian);
     }
+
+    fn push_var1_entry(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, config: &Config) {
+        let this: &[T] = self;
+        this.push_var1_entry(var_length, data, config);
+    }
+
+    fn measure_var1_entry(&self, counter: &mut CountingEncoder) {
+        let this: &[T] = self;
+        this.measure_var1_entry(counter);
+    }
+}
+
+impl<T> DataType for std::ops::Range<T>
+where
+    T: DataType,
+{
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = array_length(T::LENGTH, 2);
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        if T::MODE != DataMode::Fixed {
+            panic!("fixed ranges require fixed data types");
+        }
+        self.start.push_fixed_data(encoder_fixed, endian);
+        self.end.push_fixed_data(encoder_fixed, endian);
+    }
+
+    fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
+        self.push_fixed_data(data, endian);
+        var_length.push(Self::LENGTH as u32);
+    }
+}
+
+impl<T> DataType for std::ops::RangeInclusive<T>
+where
+    T: DataType,
+{
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = array_length(T::LENGTH, 2);
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        if T::MODE != DataMode::Fixed {
+            panic!("fixed ranges require fixed data types");
+        }
+        self.start().push_fixed_data(encoder_fixed, endian);
+        self.end().push_fixed_data(encoder_fixed, endian);
+    }
+
+    fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
+        self.push_fixed_data(data, endian);
+        var_length.push(Self::LENGTH as u32);
+    }
+}
+
+/// As a vector element, an option is fixed-width: a presence byte followed by `T`'s bytes, or
+/// by `T::LENGTH` zero bytes when `None`. (A standalone `Option` field omits the padding.)
+impl<T> DataType for Option<T>
+where
+    T: DataType,
+{
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = 1 + T::LENGTH;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        if T::MODE != DataMode::Fixed {
+            panic!("fixed options require fixed data types");
+        }
+        match self {
+            Some(value) => {
+                encoder_fixed.push(1);
+                value.push_fixed_data(encoder_fixed, endian);
+            }
+            None => {
+                encoder_fixed.push(0);
+                encoder_fixed.resize(encoder_fixed.len() + T::LENGTH, 0);
+            }
+        }
+    }
+
+    fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
+        self.push_fixed_data(data, endian);
+        var_length.push(Self::LENGTH as u32);
+    }
 }
@@ -1,7 +1,7 @@
 //! Decoding support for pufu payloads.
 
 use crate::fixed_decode::FixedDecode;
-use crate::{CodecError, Decoder, Endian};
+use crate::{CodecError, Config, Decoder};
 
 /// Decodes a single field from a decoder and exposes a view into the buffer.
 pub trait Decode {
@@ -14,6 +14,53 @@ pub trait Decode {
     fn decode_field<'a, const IS_LAST_VAR: bool>(
         decoder: &mut Decoder<'a>,
     ) -> Result<Self::View<'a>, CodecError>;
+
+    /// Decode a top-level value from a little-endian payload (no magic or version).
+    fn decode_le<'a>(buf: &'a [u8]) -> Result<Self::View<'a>, CodecError>
+    where
+        Self: 'a,
+    {
+        let mut decoder = Decoder::new(buf, Config::builder().little().build())?;
+        Self::decode_field::<true>(&mut decoder)
+    }
+
+    /// Decode a top-level value from a big-endian payload (no magic or version).
+    fn decode_be<'a>(buf: &'a [u8]) -> Result<Self::View<'a>, CodecError>
+    where
+        Self: 'a,
+    {
+        let mut decoder = Decoder::new(buf, Config::builder().big().build())?;
+        Self::decode_field::<true>(&mut decoder)
+    }
+
+    /// Decode a top-level value from a full payload, checking magic and version against `config`.
+    fn decode_with<'a>(buf: &'a [u8], config: &Config) -> Result<Self::View<'a>, CodecError>
+    where
+        Self: 'a,
+    {
+        let mut decoder = Decoder::new_with_magic_version(buf, config.clone())?;
+        Self::decode_field::<true>(&mut decoder)
+    }
+}
+
+/// Decodes owned container fields into a caller-supplied value, reusing its allocation.
+pub trait DecodeInto: Decode {
+    /// Clear `out` and refill it from the decoder, keeping existing capacity.
+    fn decode_field_into<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+        out: &mut Self,
+    ) -> Result<(), CodecError>;
+}
+
+/// Decodes a field into an owned value that can outlive the payload buffer.
+pub trait DecodeOwned: Decode {
+    /// Owned value produced by decoding.
+    type Output;
+
+    /// Decode this field and copy it out of the buffer.
+    fn decode_owned_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::Output, CodecError>;
 }
 
 /// Decode one fixed-width value from the fixed region.
@@ -26,19 +73,57 @@ where
 }
 
 /// Decode a fixed-width slice into owned values.
-fn decode_fixed_slice<T>(bytes: &[u8], endian: Endian) -> Result<Vec<T>, CodecError>
+fn decode_fixed_slice<T>(bytes: &[u8], config: &Config) -> Result<Vec<T>, CodecError>
+where
+    T: FixedDecode,
+{
+    let mut out = Vec::new();
+    decode_fixed_slice_into(bytes, config, &mut out)?;
+    Ok(out)
+}
+
+/// Decode a fixed-width slice into `out`, clearing it first and reusing its capacity.
+fn decode_fixed_slice_into<T>(
+    bytes: &[u8],
+    config: &Config,
+    out: &mut Vec<T>,
+) -> Result<(), CodecError>
 where
     T: FixedDecode,
 {
-    if T::LENGTH == 0 || !bytes.len().is_multiple_of(T::LENGTH) {
+    out.clear();
+    decode_fixed_slice_append(bytes, config, out)
+}
+
+/// Decode a fixed-width slice onto the end of `out`, keeping what it already holds.
+pub(crate) fn decode_fixed_slice_append<T>(
+    bytes: &[u8],
+    config: &Config,
+    out: &mut Vec<T>,
+) -> Result<(), CodecError>
+where
+    T: FixedDecode,
+{
+    // Zero-length elements carry no count, so only an empty segment (an empty Vec) is valid.
+    if T::LENGTH == 0 {
+        if !bytes.is_empty() {
+            return Err(CodecError::InvalidLength);
+        }
+        return Ok(());
+    }
+    if !bytes.len().is_multiple_of(T::LENGTH) {
         return Err(CodecError::InvalidLength);
     }
+    let count = bytes.len() / T::LENGTH;
+    if config.max_elements.is_some_and(|max| count > max) {
+        return Err(CodecError::ValidationFailed);
+    }
 
-    let mut out = Vec::with_capacity(bytes.len() / T::LENGTH);
+    out.reserve(count);
     for chunk in bytes.chunks_exact(T::LENGTH) {
-        out.push(T::decode(chunk, endian)?);
+        out.push(T::decode(chunk, config.endian)?);
     }
-    Ok(out)
+    Ok(())
 }
 
 /// Return a borrowed view for a u8 slice payload.
@@ -55,7 +140,7 @@ macro_rules! impl_not_u8_for_primitive {
     };
 }
 
-impl_not_u8_for_primitive!(u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_not_u8_for_primitive!(bool, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 
 impl<T, const N: usize> NotU8 for [T; N] where T: FixedDecode {}
 
@@ -66,6 +151,24 @@ where
 {
 }
 
+impl<T0, T1, T2> NotU8 for (T0, T1, T2)
+where
+    T0: FixedDecode,
+    T1: FixedDecode,
+    T2: FixedDecode,
+{
+}
+
+impl<T> NotU8 for std::num::Wrapping<T> where T: FixedDecode {}
+
+impl<T> NotU8 for Option<T> where T: FixedDecode {}
+
+impl<T> NotU8 for std::ops::Range<T> where T: FixedDecode {}
+
+impl<T> NotU8 for std::ops::RangeInclusive<T> where T: FixedDecode {}
+
+impl<T> NotU8 for std::cmp::Reverse<T> where T: FixedDecode {}
+
 macro_rules! impl_field_decode_for_fixed_primitive {
     ($($t:ty),* $(,)?) => {
         $(
@@ -84,7 +187,7 @@ macro_rules! impl_field_decode_for_fixed_primitive {
 }
 
 impl_field_decode_for_fixed_primitive!(
-    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+    bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
 );
 
 impl<const N: usize> Decode for [u8; N] {
@@ -117,32 +220,118 @@ where
         let _ = IS_LAST_VAR;
         let len = T::LENGTH.checked_mul(N).ok_or(CodecError::InvalidLength)? as u32;
         let bytes = decoder.next_fixed_bytes(len)?;
-        let items = decode_fixed_slice::<T>(bytes, decoder.config().endian)?;
-        items.try_into().map_err(|_| CodecError::InvalidLength)
+        <[T; N]>::decode(bytes, decoder.config().endian)
     }
 }
 
+/// Tuples decode their elements in order, mirroring `Encode`; only the last element inherits
+/// `IS_LAST_VAR`.
 impl<T0, T1> Decode for (T0, T1)
 where
-    T0: FixedDecode + NotU8 + 'static,
-    T1: FixedDecode + NotU8 + 'static,
+    T0: Decode,
+    T1: Decode,
 {
     type View<'a>
-        = (T0, T1)
+        = (T0::View<'a>, T1::View<'a>)
     where
         T0: 'a,
         T1: 'a;
 
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        let t0 = T0::decode_field::<false>(decoder)?;
+        let t1 = T1::decode_field::<IS_LAST_VAR>(decoder)?;
+        Ok((t0, t1))
+    }
+}
+
+impl<T0, T1, T2> Decode for (T0, T1, T2)
+where
+    T0: Decode,
+    T1: Decode,
+    T2: Decode,
+{
+    type View<'a>
+        = (T0::View<'a>, T1::View<'a>, T2::View<'a>)
+    where
+        T0: 'a,
+        T1: 'a,
+        T2: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        let t0 = T0::decode_field::<false>(decoder)?;
+        let t1 = T1::decode_field::<false>(decoder)?;
+        let t2 = T2::decode_field::<IS_LAST_VAR>(decoder)?;
+        Ok((t0, t1, t2))
+    }
+}
+
+impl<T> Decode for std::ops::Range<T>
+where
+    T: FixedDecode + 'static,
+{
+    type View<'a>
+        = std::ops::Range<T>
+    where
+        T: 'a;
+
     fn decode_field<'a, const IS_LAST_VAR: bool>(
         decoder: &mut Decoder<'a>,
     ) -> Result<Self::View<'a>, CodecError> {
         let _ = IS_LAST_VAR;
-        let t0_bytes = decoder.next_fixed_bytes(T0::LENGTH as u32)?;
-        let t1_bytes = decoder.next_fixed_bytes(T1::LENGTH as u32)?;
-        Ok((
-            T0::decode(t0_bytes, decoder.config().endian)?,
-            T1::decode(t1_bytes, decoder.config().endian)?,
-        ))
+        decode_fixed_value(decoder)
+    }
+}
+
+impl<T> Decode for std::ops::RangeInclusive<T>
+where
+    T: FixedDecode + 'static,
+{
+    type View<'a>
+        = std::ops::RangeInclusive<T>
+    where
+        T: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        let _ = IS_LAST_VAR;
+        decode_fixed_value(decoder)
+    }
+}
+
+impl<T> Decode for std::num::Wrapping<T>
+where
+    T: Decode,
+{
+    type View<'a>
+        = std::num::Wrapping<T::View<'a>>
+    where
+        T: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        T::decode_field::<IS_LAST_VAR>(decoder).map(std::num::Wrapping)
+    }
+}
+
+impl<T> Decode for std::cmp::Reverse<T>
+where
+    T: Decode,
+{
+    type View<'a>
+        = std::cmp::Reverse<T::View<'a>>
+    where
+        T: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        T::decode_field::<IS_LAST_VAR>(decoder).map(std::cmp::Reverse)
     }
 }
 
@@ -160,7 +349,23 @@ where
     ) -> Result<Self::View<'a>, CodecError> {
         let _ = IS_LAST_VAR;
         let bytes = decoder.next_var()?;
-        decode_fixed_slice::<T>(bytes, decoder.config().endian)
+        decode_fixed_slice::<T>(bytes, decoder.config())
+    }
+}
+
+impl<T> Decode for std::collections::VecDeque<T>
+where
+    T: FixedDecode + NotU8 + 'static,
+{
+    type View<'a>
+        = std::collections::VecDeque<T>
+    where
+        T: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        Vec::<T>::decode_field::<IS_LAST_VAR>(decoder).map(Into::into)
     }
 }
 
@@ -180,16 +385,55 @@ where
             return Err(CodecError::InvalidLength);
         }
 
-        let mut out = Vec::new();
         let count = decoder.var_count();
+        let mut out = Vec::with_capacity((count - decoder.var_cursor) as usize);
         while decoder.var_cursor < count {
             let bytes = decoder.next_var()?;
-            out.push(decode_fixed_slice::<T>(bytes, decoder.config().endian)?);
+            out.push(decode_fixed_slice::<T>(bytes, decoder.config())?);
         }
         Ok(out)
     }
 }
 
+impl<T> DecodeInto for Vec<T>
+where
+    T: FixedDecode + NotU8 + 'static,
+{
+    fn decode_field_into<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+        out: &mut Self,
+    ) -> Result<(), CodecError> {
+        let _ = IS_LAST_VAR;
+        let bytes = decoder.next_var()?;
+        decode_fixed_slice_into(bytes, decoder.config(), out)
+    }
+}
+
+impl<T> DecodeInto for Vec<Vec<T>>
+where
+    T: FixedDecode + NotU8 + 'static,
+{
+    fn decode_field_into<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+        out: &mut Self,
+    ) -> Result<(), CodecError> {
+        if !IS_LAST_VAR {
+            return Err(CodecError::InvalidLength);
+        }
+
+        // Inner vectors beyond the decoded row count are dropped; the rest are refilled in place.
+        let count = decoder.var_count();
+        let rows = (count - decoder.var_cursor) as usize;
+        out.truncate(rows);
+        out.resize_with(rows, Vec::new);
+        for row in out.iter_mut() {
+            let bytes = decoder.next_var()?;
+            decode_fixed_slice_into(bytes, decoder.config(), row)?;
+        }
+        Ok(())
+    }
+}
+
 impl Decode for Vec<u8> {
     type View<'a>
         = &'a [u8]
@@ -205,6 +449,118 @@ impl Decode for Vec<u8> {
     }
 }
 
+impl DecodeOwned for Vec<u8> {
+    type Output = Vec<u8>;
+
+    fn decode_owned_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::Output, CodecError> {
+        Self::decode_field::<IS_LAST_VAR>(decoder).map(<[u8]>::to_vec)
+    }
+}
+
+impl<T> Decode for Option<T>
+where
+    T: Decode,
+{
+    type View<'a>
+        = Option<T::View<'a>>
+    where
+        T: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        match u8::decode_field::<false>(decoder)? {
+            0 => Ok(None),
+            1 => T::decode_field::<IS_LAST_VAR>(decoder).map(Some),
+            _ => Err(CodecError::ValidationFailed),
+        }
+    }
+}
+
+impl Decode for Box<[u8]> {
+    type View<'a> = &'a [u8];
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        Vec::<u8>::decode_field::<IS_LAST_VAR>(decoder)
+    }
+}
+
+impl Decode for Box<str> {
+    type View<'a> = &'a str;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        let _ = IS_LAST_VAR;
+        let bytes = decoder.next_var()?;
+        std::str::from_utf8(bytes).map_err(|_| CodecError::InvalidUtf8)
+    }
+}
+
+/// Decodes as a borrowed `&CStr`. The segment must end in a nul byte and contain no other nul,
+/// otherwise decoding fails with `CodecError::ValidationFailed`.
+impl Decode for std::ffi::CString {
+    type View<'a> = &'a std::ffi::CStr;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        let _ = IS_LAST_VAR;
+        let bytes = decoder.next_var()?;
+        std::ffi::CStr::from_bytes_with_nul(bytes).map_err(|_| CodecError::ValidationFailed)
+    }
+}
+
+impl Decode for Box<std::ffi::CStr> {
+    type View<'a> = &'a std::ffi::CStr;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        std::ffi::CString::decode_field::<IS_LAST_VAR>(decoder)
+    }
+}
+
+/// Decodes as a borrowed `&str`; owned fields use `Cow<'static, str>`.
+impl Decode for std::borrow::Cow<'static, str> {
+    type View<'a> = &'a str;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        Box::<str>::decode_field::<IS_LAST_VAR>(decoder)
+    }
+}
+
+/// Decodes as a borrowed `&[u8]`, like `Vec<u8>`.
+impl Decode for std::borrow::Cow<'static, [u8]> {
+    type View<'a> = &'a [u8];
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        Vec::<u8>::decode_field::<IS_LAST_VAR>(decoder)
+    }
+}
+
+/// Decodes into an owned `Vec<T>`, like `Vec<T>`: wider elements need byte-order conversion.
+impl<T> Decode for std::borrow::Cow<'static, [T]>
+where
+    T: FixedDecode + NotU8 + Clone + 'static,
+{
+    type View<'a> = Vec<T>;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        Vec::<T>::decode_field::<IS_LAST_VAR>(decoder)
+    }
+}
+
 impl Decode for Vec<Vec<u8>> {
     type View<'a>
         = Vec<&'a [u8]>
@@ -218,8 +574,8 @@ impl Decode for Vec<Vec<u8>> {
             return Err(CodecError::InvalidLength);
         }
 
-        let mut out = Vec::new();
         let count = decoder.var_count();
+        let mut out = Vec::with_capacity((count - decoder.var_cursor) as usize);
         while decoder.var_cursor < count {
             let bytes = decoder.next_var()?;
             out.push(decode_fixed_slice_u8_ref(bytes)?);
@@ -228,9 +584,57 @@ impl Decode for Vec<Vec<u8>> {
     }
 }
 
+/// Decodes every remaining var entry as a nested payload whose only var field is a `T`.
+fn decode_nested_entries<'a, T: Decode, const IS_LAST_VAR: bool>(
+    decoder: &mut Decoder<'a>,
+) -> Result<Vec<T::View<'a>>, CodecError> {
+    if !IS_LAST_VAR {
+        return Err(CodecError::InvalidLength);
+    }
+
+    let count = decoder.var_count();
+    let mut out = Vec::with_capacity((count - decoder.var_cursor) as usize);
+    while decoder.var_cursor < count {
+        let bytes = decoder.next_var()?;
+        let mut nested = Decoder::new(bytes, decoder.config().clone())?;
+        out.push(T::decode_field::<true>(&mut nested)?);
+    }
+    Ok(out)
+}
+
+/// Three-level vectors: each middle `Vec<Vec<T>>` is one var entry holding a nested payload.
+impl<T> Decode for Vec<Vec<Vec<T>>>
+where
+    T: FixedDecode + NotU8 + 'static,
+{
+    type View<'a>
+        = Vec<Vec<Vec<T>>>
+    where
+        T: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        decode_nested_entries::<Vec<Vec<T>>, IS_LAST_VAR>(decoder)
+    }
+}
+
+impl Decode for Vec<Vec<Vec<u8>>> {
+    type View<'a>
+        = Vec<Vec<&'a [u8]>>
+    where
+        u8: 'a;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        decode_nested_entries::<Vec<Vec<u8>>, IS_LAST_VAR>(decoder)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Decode;
+    use super::{Decode, DecodeInto, DecodeOwned};
     use crate::{CodecError, Config, Decoder, Encode, Encoder};
 
     #[test]
@@ -293,7 +697,13 @@ mod tests {
         let buf = vec![8, 0, 0, 0, 8, 0, 0, 0];
         let mut decoder = Decoder::new(&buf, Config::default()).expect("decoder");
         let decoded = <[u16; 2]>::decode_field::<false>(&mut decoder);
-        assert_eq!(decoded, Err(CodecError::InvalidLength));
+        assert_eq!(
+            decoded,
+            Err(CodecError::Truncated {
+                needed: 4,
+                available: 0
+            })
+        );
     }
 
     #[test]
@@ -385,8 +795,9 @@ mod tests {
         assert_eq!(decoded, arr);
 
         let mut arr_mut: [u16; 2] = [0x1112, 0x1314];
+        let arr_mut_ref: &mut [u16; 2] = &mut arr_mut;
         let mut encoder2 = Encoder::new(Config::default());
-        (&mut arr_mut).encode_field::<true>(&mut encoder2);
+        arr_mut_ref.encode_field::<true>(&mut encoder2);
         let mut out2 = Vec::new();
         encoder2.finalize(&mut out2).expect("finalize");
         let mut decoder2 = Decoder::new(&out2, Config::default()).expect("decoder");
@@ -408,8 +819,9 @@ mod tests {
         assert_eq!(decoded, vec_data.as_slice());
 
         let mut vec_mut: Vec<u16> = vec![0x0102, 0x0304];
+        let vec_mut_ref: &mut Vec<u16> = &mut vec_mut;
         let mut encoder2 = Encoder::new(Config::default());
-        (&mut vec_mut).encode_field::<true>(&mut encoder2);
+        vec_mut_ref.encode_field::<true>(&mut encoder2);
         let mut out2 = Vec::new();
         encoder2.finalize(&mut out2).expect("finalize");
         let mut decoder2 = Decoder::new(&out2, Config::default()).expect("decoder");
@@ -425,4 +837,580 @@ mod tests {
         let decoded3 = Vec::<u16>::decode_field::<true>(&mut decoder3).expect("vec");
         assert_eq!(decoded3, slice);
     }
+
+    #[test]
+    fn decode_wrapping_and_reverse_roundtrip() {
+        let mut encoder = Encoder::new(Config::default());
+        let counter = std::num::Wrapping(0x0102_0304u32);
+        let rank = std::cmp::Reverse(0x0506u16);
+        let history: Vec<std::num::Wrapping<u32>> =
+            vec![std::num::Wrapping(1), std::num::Wrapping(u32::MAX)];
+
+        counter.encode_field::<false>(&mut encoder);
+        rank.encode_field::<false>(&mut encoder);
+        history.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+
+        let decoded_counter =
+            std::num::Wrapping::<u32>::decode_field::<false>(&mut decoder).expect("wrapping");
+        let decoded_rank =
+            std::cmp::Reverse::<u16>::decode_field::<false>(&mut decoder).expect("reverse");
+        let decoded_history =
+            Vec::<std::num::Wrapping<u32>>::decode_field::<true>(&mut decoder).expect("vec");
+
+        assert_eq!(decoded_counter, counter);
+        assert_eq!(decoded_rank, rank);
+        assert_eq!(decoded_history, history);
+    }
+
+    #[test]
+    fn decode_vec_of_fixed_pairs_and_triples_roundtrip() {
+        let mut encoder = Encoder::new(Config::default());
+        let triple: (u8, u16, u32) = (0x01, 0x0203, 0x0405_0607);
+        let pairs: Vec<(u32, u32)> = vec![(1, 2), (3, 4), (0x0a0b_0c0d, u32::MAX)];
+        let triples: Vec<(u8, u16, u32)> = vec![(5, 6, 7), (8, 9, 10)];
+
+        triple.encode_field::<false>(&mut encoder);
+        pairs.encode_field::<false>(&mut encoder);
+        triples.encode_field::<true>(&mut encoder);
+        assert_eq!(encoder.var_length, vec![24, 14]);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+
+        let decoded_triple = <(u8, u16, u32)>::decode_field::<false>(&mut decoder).expect("triple");
+        let decoded_pairs = Vec::<(u32, u32)>::decode_field::<false>(&mut decoder).expect("pairs");
+        let decoded_triples =
+            Vec::<(u8, u16, u32)>::decode_field::<true>(&mut decoder).expect("triples");
+
+        assert_eq!(decoded_triple, triple);
+        assert_eq!(decoded_pairs, pairs);
+        assert_eq!(decoded_triples, triples);
+    }
+
+    #[test]
+    fn decode_into_reuses_vec_allocations() {
+        let mut encoder = Encoder::new(Config::default());
+        let flat: Vec<u16> = vec![1, 2, 3];
+        let nested: Vec<Vec<u16>> = vec![vec![4, 5], vec![6]];
+        flat.encode_field::<false>(&mut encoder);
+        nested.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut flat_buf: Vec<u16> = Vec::new();
+        let mut nested_buf: Vec<Vec<u16>> = Vec::new();
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        Vec::<u16>::decode_field_into::<false>(&mut decoder, &mut flat_buf).expect("flat");
+        Vec::<Vec<u16>>::decode_field_into::<true>(&mut decoder, &mut nested_buf).expect("nested");
+        assert_eq!(flat_buf, flat);
+        assert_eq!(nested_buf, nested);
+
+        let flat_ptr = flat_buf.as_ptr();
+        let flat_cap = flat_buf.capacity();
+        let row_ptrs: Vec<*const u16> = nested_buf.iter().map(|row| row.as_ptr()).collect();
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        Vec::<u16>::decode_field_into::<false>(&mut decoder, &mut flat_buf).expect("flat");
+        Vec::<Vec<u16>>::decode_field_into::<true>(&mut decoder, &mut nested_buf).expect("nested");
+        assert_eq!(flat_buf, flat);
+        assert_eq!(nested_buf, nested);
+        assert_eq!(flat_buf.as_ptr(), flat_ptr);
+        assert_eq!(flat_buf.capacity(), flat_cap);
+        let reused_ptrs: Vec<*const u16> = nested_buf.iter().map(|row| row.as_ptr()).collect();
+        assert_eq!(reused_ptrs, row_ptrs);
+    }
+
+    #[test]
+    fn decode_var2_preallocates_outer_rows() {
+        let mut encoder = Encoder::new(Config::default());
+        let head: Vec<u8> = vec![0xff];
+        let rows: Vec<Vec<u16>> = vec![vec![1], vec![2, 3], vec![], vec![4]];
+        head.encode_field::<false>(&mut encoder);
+        rows.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+
+        Vec::<u8>::decode_field::<false>(&mut decoder).expect("head");
+        let decoded = Vec::<Vec<u16>>::decode_field::<true>(&mut decoder).expect("rows");
+        assert_eq!(decoded, rows);
+        assert_eq!(decoded.capacity(), rows.len());
+    }
+
+    #[test]
+    fn decode_boxed_bytes_and_str_roundtrip() {
+        let mut encoder = Encoder::new(Config::default());
+        let bytes: Box<[u8]> = vec![0x01, 0x02, 0x03].into_boxed_slice();
+        let text: Box<str> = "pufu".into();
+        bytes.encode_field::<false>(&mut encoder);
+        text.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+
+        let decoded_bytes = Box::<[u8]>::decode_field::<false>(&mut decoder).expect("bytes");
+        let decoded_text = Box::<str>::decode_field::<true>(&mut decoder).expect("text");
+        assert_eq!(decoded_bytes, &*bytes);
+        assert_eq!(decoded_text, &*text);
+    }
+
+    #[test]
+    fn decode_boxed_str_rejects_invalid_utf8() {
+        let mut encoder = Encoder::new(Config::default());
+        let bytes: Vec<u8> = vec![0xff, 0xfe];
+        bytes.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            Box::<str>::decode_field::<true>(&mut decoder),
+            Err(CodecError::InvalidUtf8)
+        );
+    }
+
+    #[test]
+    fn decode_le_and_be_interpret_fixed_bytes_differently() {
+        let fixed = [0x01, 0x02];
+        let mut le_buf = Vec::new();
+        le_buf.extend_from_slice(&10u32.to_le_bytes());
+        le_buf.extend_from_slice(&10u32.to_le_bytes());
+        le_buf.extend_from_slice(&fixed);
+        let mut be_buf = Vec::new();
+        be_buf.extend_from_slice(&10u32.to_be_bytes());
+        be_buf.extend_from_slice(&10u32.to_be_bytes());
+        be_buf.extend_from_slice(&fixed);
+
+        assert_eq!(u16::decode_le(&le_buf), Ok(0x0201));
+        assert_eq!(u16::decode_be(&be_buf), Ok(0x0102));
+        assert_eq!(
+            u16::decode_be(&le_buf),
+            Err(CodecError::Truncated {
+                needed: 10 << 24,
+                available: 10
+            })
+        );
+    }
+
+    #[test]
+    fn decode_vec_rejects_segment_over_max_elements() {
+        let mut encoder = Encoder::new(Config::default());
+        vec![1u32, 2, 3, 4].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let at_limit = Config::builder().max_elements(4).build();
+        let mut decoder = Decoder::new(&out, at_limit).expect("decoder");
+        assert_eq!(
+            <Vec<u32>>::decode_field::<true>(&mut decoder),
+            Ok(vec![1, 2, 3, 4])
+        );
+
+        let over_limit = Config::builder().max_elements(3).build();
+        let mut decoder = Decoder::new(&out, over_limit).expect("decoder");
+        assert_eq!(
+            <Vec<u32>>::decode_field::<true>(&mut decoder),
+            Err(CodecError::ValidationFailed)
+        );
+    }
+
+    #[test]
+    fn roundtrip_vec_deque_including_wrapped_buffer() {
+        use std::collections::VecDeque;
+
+        let contiguous: VecDeque<u32> = VecDeque::from(vec![1, 2, 3]);
+        let mut wrapped: VecDeque<u32> = VecDeque::with_capacity(4);
+        wrapped.extend([10, 11, 12, 13]);
+        wrapped.pop_front();
+        wrapped.pop_front();
+        wrapped.push_back(14);
+        wrapped.push_back(15);
+        assert!(!wrapped.as_slices().1.is_empty());
+
+        let mut encoder = Encoder::new(Config::default());
+        contiguous.encode_field::<false>(&mut encoder);
+        wrapped.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let decoded_contiguous =
+            <VecDeque<u32>>::decode_field::<false>(&mut decoder).expect("contiguous");
+        let decoded_wrapped = <VecDeque<u32>>::decode_field::<true>(&mut decoder).expect("wrapped");
+        assert_eq!(decoded_contiguous, contiguous);
+        assert_eq!(decoded_wrapped, VecDeque::from(vec![12, 13, 14, 15]));
+    }
+
+    #[test]
+    fn roundtrip_tuple_with_var_elements() {
+        let value: (u32, Vec<u8>, Vec<Vec<u16>>) = (
+            0x0102_0304,
+            vec![9, 8, 7],
+            vec![vec![1, 2], vec![], vec![3]],
+        );
+
+        let mut encoder = Encoder::new(Config::default());
+        value.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let (id, bytes, rows) =
+            <(u32, Vec<u8>, Vec<Vec<u16>>)>::decode_field::<true>(&mut decoder).expect("tuple");
+        assert_eq!(id, value.0);
+        assert_eq!(bytes, value.1.as_slice());
+        // The var2 element only decodes when it receives IS_LAST_VAR == true.
+        assert_eq!(rows, value.2);
+        assert_eq!(decoder.var_cursor, decoder.var_count());
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <(u32, Vec<u8>, Vec<Vec<u16>>)>::decode_field::<false>(&mut decoder).err(),
+            Some(CodecError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn decode_option_bytes_reads_var_entry_only_when_present() {
+        let mut encoder = Encoder::new(Config::default());
+        Some(vec![1u8, 2]).encode_field::<false>(&mut encoder);
+        None::<Vec<u8>>.encode_field::<false>(&mut encoder);
+        vec![3u8].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.var_count(), 2);
+        assert_eq!(
+            <Option<Vec<u8>>>::decode_field::<false>(&mut decoder),
+            Ok(Some(&[1u8, 2][..]))
+        );
+        assert_eq!((decoder.fixed_cursor, decoder.var_cursor), (1, 1));
+        assert_eq!(
+            <Option<Vec<u8>>>::decode_field::<false>(&mut decoder),
+            Ok(None)
+        );
+        assert_eq!((decoder.fixed_cursor, decoder.var_cursor), (2, 1));
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[3u8][..])
+        );
+    }
+
+    #[test]
+    fn decode_option_rejects_invalid_presence_byte() {
+        let mut encoder = Encoder::new(Config::default());
+        2u8.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <Option<u32>>::decode_field::<true>(&mut decoder),
+            Err(CodecError::ValidationFailed)
+        );
+    }
+
+    #[test]
+    fn roundtrip_ranges() {
+        let half_open = 0u32..100;
+        let inclusive = 1u8..=255;
+        let history: Vec<std::ops::Range<u16>> = vec![0..1, 3..u16::MAX];
+
+        let mut encoder = Encoder::new(Config::builder().big().build());
+        half_open.encode_field::<false>(&mut encoder);
+        inclusive.encode_field::<false>(&mut encoder);
+        history.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        assert_eq!(decoder.fixed_region_len(), 8 + 2);
+        assert_eq!(
+            <std::ops::Range<u32>>::decode_field::<false>(&mut decoder),
+            Ok(half_open)
+        );
+        assert_eq!(
+            <std::ops::RangeInclusive<u8>>::decode_field::<false>(&mut decoder),
+            Ok(inclusive)
+        );
+        assert_eq!(
+            <Vec<std::ops::Range<u16>>>::decode_field::<true>(&mut decoder),
+            Ok(history)
+        );
+    }
+
+    #[test]
+    fn roundtrip_byte_arrays_larger_than_32() {
+        let a64: [u8; 64] = std::array::from_fn(|i| i as u8);
+        let a128: [u8; 128] = std::array::from_fn(|i| (i * 3) as u8);
+        let a256: [u8; 256] = std::array::from_fn(|i| !(i as u8));
+        let digests: Vec<[u8; 64]> = vec![a64, [0xff; 64]];
+
+        let mut encoder = Encoder::new(Config::default());
+        a64.encode_field::<false>(&mut encoder);
+        a128.encode_field::<false>(&mut encoder);
+        a256.encode_field::<false>(&mut encoder);
+        digests.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.fixed_region_len(), 64 + 128 + 256);
+        assert_eq!(<[u8; 64]>::decode_field::<false>(&mut decoder), Ok(&a64));
+        assert_eq!(<[u8; 128]>::decode_field::<false>(&mut decoder), Ok(&a128));
+        assert_eq!(<[u8; 256]>::decode_field::<false>(&mut decoder), Ok(&a256));
+        assert_eq!(
+            <Vec<[u8; 64]>>::decode_field::<true>(&mut decoder),
+            Ok(digests)
+        );
+    }
+
+    #[test]
+    fn cow_str_encodes_identically_when_borrowed_or_owned() {
+        use std::borrow::Cow;
+
+        let encode = |value: &Cow<'_, str>| {
+            let mut encoder = Encoder::new(Config::default());
+            value.encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            out
+        };
+        let borrowed = encode(&Cow::Borrowed("héllo"));
+        let owned = encode(&Cow::Owned(String::from("héllo")));
+        assert_eq!(borrowed, owned);
+
+        let mut decoder = Decoder::new(&owned, Config::default()).expect("decoder");
+        assert_eq!(
+            <Cow<'static, str>>::decode_field::<true>(&mut decoder),
+            Ok("héllo")
+        );
+
+        let mut invalid = encode(&Cow::Borrowed("x"));
+        let last = invalid.len() - 1;
+        invalid[last] = 0xff;
+        let mut decoder = Decoder::new(&invalid, Config::default()).expect("decoder");
+        assert_eq!(
+            <Cow<'static, str>>::decode_field::<true>(&mut decoder),
+            Err(CodecError::InvalidUtf8)
+        );
+    }
+
+    #[test]
+    fn cow_slice_encodes_identically_when_borrowed_or_owned() {
+        use std::borrow::Cow;
+
+        let encode = |value: &Cow<'_, [u16]>| {
+            let mut encoder = Encoder::new(Config::builder().big().build());
+            value.encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            out
+        };
+        let values = [1u16, 0x0203, u16::MAX];
+        let borrowed = encode(&Cow::Borrowed(&values[..]));
+        let owned = encode(&Cow::Owned(values.to_vec()));
+        assert_eq!(borrowed, owned);
+
+        let mut decoder = Decoder::new(&owned, Config::builder().big().build()).expect("decoder");
+        assert_eq!(
+            <Cow<'static, [u16]>>::decode_field::<true>(&mut decoder),
+            Ok(values.to_vec())
+        );
+
+        let bytes: Cow<'_, [u8]> = Cow::Borrowed(&[9, 8, 7]);
+        let mut encoder = Encoder::new(Config::default());
+        bytes.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <Cow<'static, [u8]>>::decode_field::<true>(&mut decoder),
+            Ok(&[9, 8, 7][..])
+        );
+    }
+
+    #[test]
+    fn roundtrip_vec_of_fixed_options() {
+        let value: Vec<Option<u32>> = vec![Some(1), None, Some(3)];
+
+        let mut encoder = Encoder::new(Config::default());
+        value.encode_field::<true>(&mut encoder);
+        assert_eq!(encoder.var_lengths(), &[15]);
+        assert_eq!(&encoder.data_bytes()[5..10], &[0; 5]);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <Vec<Option<u32>>>::decode_field::<true>(&mut decoder),
+            Ok(value)
+        );
+    }
+
+    #[test]
+    fn roundtrip_bool_array_and_reject_non_boolean_byte() {
+        let flags = [true, false, false, true, true, false, true, false];
+
+        let mut encoder = Encoder::new(Config::default());
+        flags.encode_field::<true>(&mut encoder);
+        assert_eq!(encoder.fixed_bytes(), &[1, 0, 0, 1, 1, 0, 1, 0]);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(<[bool; 8]>::decode_field::<true>(&mut decoder), Ok(flags));
+
+        let mut encoder = Encoder::new(Config::default());
+        [1u8, 2].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <[bool; 2]>::decode_field::<true>(&mut decoder),
+            Err(CodecError::ValidationFailed)
+        );
+    }
+
+    #[test]
+    fn decode_owned_bytes_match_borrowed_view() {
+        let mut encoder = Encoder::new(Config::default());
+        vec![0x10u8, 0x20, 0x30].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let borrowed = <Vec<u8>>::decode_field::<true>(&mut decoder.fork()).expect("borrowed");
+        let owned = <Vec<u8>>::decode_owned_field::<true>(&mut decoder).expect("owned");
+        assert_eq!(owned.as_slice(), borrowed);
+
+        drop(out);
+        assert_eq!(owned, vec![0x10, 0x20, 0x30]);
+    }
+
+    #[test]
+    fn zero_length_fixed_elements_roundtrip_only_when_empty() {
+        let mut encoder = Encoder::new(Config::default());
+        [0u8; 0].encode_field::<false>(&mut encoder);
+        Vec::<[u16; 0]>::new().encode_field::<true>(&mut encoder);
+        assert!(encoder.fixed_bytes().is_empty());
+        assert_eq!(encoder.var_lengths(), &[0]);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(<[u8; 0]>::decode_field::<false>(&mut decoder), Ok(&[]));
+        assert_eq!(
+            <Vec<[u16; 0]>>::decode_field::<true>(&mut decoder),
+            Ok(vec![])
+        );
+
+        let mut encoder = Encoder::new(Config::default());
+        vec![0xffu8].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <Vec<[u16; 0]>>::decode_field::<true>(&mut decoder),
+            Err(CodecError::InvalidLength)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "vectors of zero-length fixed elements must be empty")]
+    fn encode_non_empty_vec_of_zero_length_elements_panics() {
+        let mut encoder = Encoder::new(Config::default());
+        vec![[0u8; 0]; 3].encode_field::<true>(&mut encoder);
+    }
+
+    #[test]
+    fn roundtrip_three_level_vectors_as_nested_entries() {
+        let value: Vec<Vec<Vec<u16>>> = vec![
+            vec![vec![1, 2], vec![], vec![3]],
+            vec![],
+            vec![vec![u16::MAX]],
+        ];
+        let bytes: Vec<Vec<Vec<u8>>> = vec![vec![b"ab".to_vec()], vec![vec![], b"c".to_vec()]];
+
+        let mut encoder = Encoder::new(Config::default());
+        7u8.encode_field::<false>(&mut encoder);
+        value.encode_field::<true>(&mut encoder);
+        assert_eq!(encoder.var_length.len(), value.len());
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(u8::decode_field::<false>(&mut decoder), Ok(7));
+        assert_eq!(
+            <Vec<Vec<Vec<u16>>>>::decode_field::<true>(&mut decoder),
+            Ok(value.clone())
+        );
+        decoder.rewind();
+        u8::decode_field::<false>(&mut decoder).expect("u8");
+        assert_eq!(
+            <Vec<Vec<Vec<u16>>>>::decode_field::<false>(&mut decoder),
+            Err(CodecError::InvalidLength)
+        );
+
+        let mut encoder = Encoder::new(Config::default());
+        bytes.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <Vec<Vec<Vec<u8>>>>::decode_field::<true>(&mut decoder),
+            Ok(vec![vec![&b"ab"[..]], vec![&[][..], &b"c"[..]]])
+        );
+    }
+
+    #[test]
+    fn roundtrip_c_strings_with_terminating_nul() {
+        let name = std::ffi::CString::new("pufu").expect("cstring");
+        let boxed: Box<std::ffi::CStr> = c"box".into();
+
+        let mut encoder = Encoder::new(Config::default());
+        name.encode_field::<false>(&mut encoder);
+        boxed.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.peek_var(), Ok(&b"pufu\0"[..]));
+        assert_eq!(
+            std::ffi::CString::decode_field::<false>(&mut decoder),
+            Ok(name.as_c_str())
+        );
+        assert_eq!(
+            Box::<std::ffi::CStr>::decode_field::<true>(&mut decoder),
+            Ok(c"box")
+        );
+    }
+
+    #[test]
+    fn decode_c_string_rejects_missing_or_interior_nul() {
+        for bytes in [&b"pufu"[..], b"pu\0fu\0", b""] {
+            let mut encoder = Encoder::new(Config::default());
+            bytes.encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+
+            let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+            assert_eq!(
+                std::ffi::CString::decode_field::<true>(&mut decoder),
+                Err(CodecError::ValidationFailed),
+                "{bytes:?}"
+            );
+        }
+    }
 }
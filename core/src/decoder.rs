@@ -1,9 +1,10 @@
 //! Decoder for reading binary payloads (see specs/0012-decoder.md).
 
-use crate::{CodecError, Config, Decode, Endian};
+use crate::layout::{TRAILER_FLAG, TRAILER_LEN_SIZE, VAR_ENTRY_SIZE, VERSION_LEN};
+use crate::{CodecError, Config, Decode, Endian, FixedDecode};
 
 /// Reads a u32 from the first 4 bytes of `bytes` using the given endianness.
-fn read_u32_endian(bytes: &[u8], endian: Endian) -> Result<u32, CodecError> {
+pub(crate) fn read_u32_endian(bytes: &[u8], endian: Endian) -> Result<u32, CodecError> {
     let arr: [u8; 4] = bytes
         .get(0..4)
         .and_then(|s| s.try_into().ok())
@@ -27,104 +28,289 @@ pub struct Decoder<'a> {
     pub(crate) buf: &'a [u8],
     /// Total payload length in bytes (from header).
     pub(crate) total_len: u32,
+    /// Length of the trailer following the payload (from header; `0` without `TRAILER_FLAG`).
+    pub(crate) trailer_len: u32,
     /// Byte offset where the variable-length index table starts.
     pub(crate) var_idx_offset: u32,
     /// Byte offset where variable-length data starts (first byte after index table).
     pub(crate) data_offset: u32,
-    /// Current read position within the fixed region (relative to end of header).
+    /// Byte offset where the fixed region starts (header plus alignment padding).
+    pub(crate) fixed_offset: u32,
+    /// FixedRegion length in bytes (`var_idx_offset - fixed_offset`), validated once in `new`.
+    pub(crate) fixed_len: u32,
+    /// Current read position within the fixed region (relative to `fixed_offset`).
     pub(crate) fixed_cursor: u32,
     /// Current index into the variable-length entry table.
     pub(crate) var_cursor: u32,
 }
 
 impl<'a> Decoder<'a> {
-    const HEADER_LEN: u32 = 8;
-
     /// Creates a Decoder by parsing the header from `buf` using `config` for endianness.
     pub fn new(buf: &'a [u8], config: Config) -> Result<Self, CodecError> {
-        if buf.len() < Self::HEADER_LEN as usize {
-            return Err(CodecError::InvalidLength);
+        let mut header_len = config.header_len() as usize;
+        if buf.len() < header_len {
+            return Err(CodecError::Truncated {
+                needed: header_len,
+                available: buf.len(),
+            });
         }
 
+        // A compact header stores only `var_idx_offset`; the payload spans the whole buffer.
         let endian = config.endian;
-        let total_len = read_u32_endian(&buf[0..4], endian)?;
-        let var_idx_offset = read_u32_endian(&buf[4..8], endian)?;
+        let total_len = if config.compact_header {
+            u32::try_from(buf.len()).map_err(|_| CodecError::InvalidLength)?
+        } else {
+            read_u32_endian(&buf[0..4], endian)?
+        };
+        let var_pos = if config.compact_header { 0 } else { 4 };
+        let mut var_idx_offset = read_u32_endian(&buf[var_pos..var_pos + 4], endian)?;
+        let mut field_pos = var_pos + 4;
 
-        // Validate header: total_len must fit in buf; var_idx_offset must be after header and within total.
+        // `TRAILER_FLAG` adds a `trailer_len` field; compact payloads never carry a trailer, so
+        // there the flag is left in place and fails the offset checks below.
+        let mut trailer_len = 0;
+        if !config.compact_header && var_idx_offset & TRAILER_FLAG != 0 {
+            var_idx_offset &= !TRAILER_FLAG;
+            header_len += TRAILER_LEN_SIZE as usize;
+            if buf.len() < header_len {
+                return Err(CodecError::Truncated {
+                    needed: header_len,
+                    available: buf.len(),
+                });
+            }
+            trailer_len = read_u32_endian(&buf[field_pos..field_pos + 4], endian)?;
+            field_pos += TRAILER_LEN_SIZE as usize;
+        }
+
+        // Validate header: total_len and the trailer must fit in buf; var_idx_offset must be
+        // after header and within total.
         let total_len_usize = total_len as usize;
-        if total_len_usize > buf.len() {
-            return Err(CodecError::InvalidLength);
+        let needed = total_len_usize
+            .checked_add(trailer_len as usize)
+            .ok_or(CodecError::InvalidLength)?;
+        if needed > buf.len() {
+            return Err(CodecError::Truncated {
+                needed,
+                available: buf.len(),
+            });
         }
-        if var_idx_offset < Self::HEADER_LEN {
-            return Err(CodecError::InvalidLength);
+        // With `fixed_align > 1` the header records where the FixedRegion starts; the encoder
+        // chose the padding relative to its output buffer, so it is read rather than recomputed.
+        let fixed_offset = if config.records_fixed_offset() {
+            let recorded = read_u32_endian(&buf[field_pos..field_pos + 4], endian)?;
+            if (recorded as usize) < header_len {
+                return Err(CodecError::MalformedOffsets);
+            }
+            recorded
+        } else {
+            header_len as u32
+        };
+        if var_idx_offset < fixed_offset || var_idx_offset > total_len {
+            return Err(CodecError::MalformedOffsets);
         }
-        if var_idx_offset > total_len {
-            return Err(CodecError::InvalidLength);
+        // Padding between the header and the FixedRegion is always zero.
+        if buf[header_len..fixed_offset as usize]
+            .iter()
+            .any(|&b| b != 0)
+        {
+            return Err(CodecError::MalformedOffsets);
         }
+        // The FixedRegion `[fixed_offset, var_idx_offset)` lies inside `buf` because
+        // `var_idx_offset <= total_len <= buf.len()`; fixed reads rely on this and only check
+        // against the cached length.
+        let fixed_len = var_idx_offset - fixed_offset;
 
         // data_offset: when no var entries, equals var_idx_offset; otherwise read first u32 from index table.
+        // Invariant: the first VarEntry always equals the data region start, even when the first
+        // segment is empty, because segments are laid out back to back from there. The entry
+        // count is therefore `(data_offset - var_idx_offset) / VAR_ENTRY_SIZE`.
         let data_offset = if total_len == var_idx_offset {
             var_idx_offset
         } else {
             let start = usize::try_from(var_idx_offset).map_err(|_| CodecError::InvalidLength)?;
-            let end = start.checked_add(4).ok_or(CodecError::InvalidLength)?;
+            let end = start
+                .checked_add(VAR_ENTRY_SIZE as usize)
+                .ok_or(CodecError::InvalidLength)?;
             if end > buf.len() {
-                return Err(CodecError::InvalidLength);
+                return Err(CodecError::Truncated {
+                    needed: end,
+                    available: buf.len(),
+                });
             }
             read_u32_endian(&buf[start..end], endian)?
         };
 
         // data_offset must follow var table and align; index table length must be multiple of 4.
-        if data_offset < var_idx_offset {
-            return Err(CodecError::InvalidLength);
+        if data_offset < var_idx_offset
+            || data_offset > total_len
+            || (total_len > var_idx_offset && data_offset == var_idx_offset)
+            || (data_offset - var_idx_offset) % VAR_ENTRY_SIZE != 0
+        {
+            return Err(CodecError::MalformedOffsets);
         }
-        if data_offset > total_len {
-            return Err(CodecError::InvalidLength);
-        }
-        if total_len > var_idx_offset && data_offset == var_idx_offset {
-            return Err(CodecError::InvalidLength);
-        }
-        if (data_offset - var_idx_offset) % 4 != 0 {
-            return Err(CodecError::InvalidLength);
+        // The last entry must also fall inside the data region, or the inferred count is wrong.
+        if data_offset > var_idx_offset {
+            let start = (data_offset - VAR_ENTRY_SIZE) as usize;
+            let last = read_u32_endian(&buf[start..data_offset as usize], endian)?;
+            if last < data_offset || last > total_len {
+                return Err(CodecError::MalformedOffsets);
+            }
         }
 
         Ok(Self {
             config,
             buf,
             total_len,
+            trailer_len,
             var_idx_offset,
             data_offset,
+            fixed_offset,
+            fixed_len,
             fixed_cursor: 0,
             var_cursor: 0,
         })
     }
 
+    /// Creates a Decoder for a payload starting `offset` bytes into `buf`, e.g. after a caller's
+    /// own header that `Encoder::finalize` appended to.
+    pub fn new_at(buf: &'a [u8], offset: usize, config: Config) -> Result<Self, CodecError> {
+        match buf.get(offset..) {
+            Some(payload) => Self::new(payload, config),
+            None => Err(CodecError::Truncated {
+                needed: offset,
+                available: buf.len(),
+            }),
+        }
+    }
+
+    /// Creates a Decoder from a full payload written by `Encoder::finalize_with_magic_version`.
+    ///
+    /// Returns `CodecError::ValidationFailed` if the magic or version differs from `config`.
+    pub fn new_with_magic_version(buf: &'a [u8], config: Config) -> Result<Self, CodecError> {
+        let prefix_len = config.magic.len() + VERSION_LEN;
+        if buf.len() < prefix_len {
+            return Err(CodecError::Truncated {
+                needed: prefix_len,
+                available: buf.len(),
+            });
+        }
+        let (magic, rest) = buf.split_at(config.magic.len());
+        if magic != &*config.magic || rest[0] != config.version {
+            return Err(CodecError::ValidationFailed);
+        }
+        Self::new(&buf[prefix_len..], config)
+    }
+
     /// Returns a reference to the Config.
     pub fn config(&self) -> &Config {
         &self.config
     }
 
-    /// Returns the number of variable-length entries. This is `(data_offset - var_idx_offset) / 4`.
+    /// Returns the byte order values are read in, with `Endian::Native` resolved to the host's.
+    pub fn endian(&self) -> Endian {
+        self.config.endian.resolve_native()
+    }
+
+    /// Returns an independent copy of this decoder with the same cursors.
+    ///
+    /// This is the supported way to parse speculatively: advance the fork, and either discard it
+    /// or keep it in place of the original. Cursor movements never affect the other copy.
+    pub fn fork(&self) -> Decoder<'a> {
+        self.clone()
+    }
+
+    /// Returns the current `(fixed_cursor, var_cursor)` so it can be passed to `restore`.
+    pub fn position(&self) -> (u32, u32) {
+        (self.fixed_cursor, self.var_cursor)
+    }
+
+    /// Moves both cursors to a position captured by `position`.
+    ///
+    /// Returns `CodecError::InvalidLength` if the fixed cursor is past the FixedRegion or the var
+    /// cursor is past the last VarEntry; the cursors are unchanged in that case.
+    pub fn restore(&mut self, pos: (u32, u32)) -> Result<(), CodecError> {
+        let (fixed_cursor, var_cursor) = pos;
+        if fixed_cursor > self.fixed_len || var_cursor > self.var_count() {
+            return Err(CodecError::InvalidLength);
+        }
+        self.fixed_cursor = fixed_cursor;
+        self.var_cursor = var_cursor;
+        Ok(())
+    }
+
+    /// Resets the fixed and variable cursors to the start of the payload.
+    pub fn rewind(&mut self) {
+        self.fixed_cursor = 0;
+        self.var_cursor = 0;
+    }
+
+    /// Returns the canonical payload `buf[..total_len]`, excluding any trailer.
+    ///
+    /// This is the stable byte range to authenticate with an external integrity scheme.
+    pub fn payload_bytes(&self) -> &'a [u8] {
+        &self.buf[..self.total_len as usize]
+    }
+
+    /// Returns the trailer written by `Encoder::push_trailer`, as recorded in the header; empty if
+    /// the payload has none.
+    pub fn trailer(&self) -> &'a [u8] {
+        let start = self.total_len as usize;
+        &self.buf[start..start + self.trailer_len as usize]
+    }
+
+    /// Returns the unparsed bytes after this payload and its trailer, e.g. the next frame when
+    /// payloads are concatenated.
+    pub fn tail(&self) -> &'a [u8] {
+        &self.buf[self.frame_len()..]
+    }
+
+    /// Returns the number of bytes this payload occupies in `buf`: `total_len` plus the trailer.
+    pub(crate) fn frame_len(&self) -> usize {
+        self.total_len as usize + self.trailer_len as usize
+    }
+
+    /// Returns the FixedRegion length in bytes (`var_idx_offset - fixed_offset`).
+    pub fn fixed_region_len(&self) -> u32 {
+        self.fixed_len
+    }
+
+    /// Checks that the FixedRegion is exactly `len` bytes, e.g. to detect schema version drift.
+    pub fn expect_fixed_len(&self, len: u32) -> Result<(), CodecError> {
+        if self.fixed_region_len() != len {
+            return Err(CodecError::ValidationFailed);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of variable-length entries. This is `(data_offset - var_idx_offset) / VAR_ENTRY_SIZE`.
     pub fn var_count(&self) -> u32 {
-        (self.data_offset - self.var_idx_offset) / 4
+        (self.data_offset - self.var_idx_offset) / VAR_ENTRY_SIZE
     }
 
     /// Reads the next `len` bytes from the FixedRegion, advancing `fixed_cursor`.
     pub fn next_fixed_bytes(&mut self, len: u32) -> Result<&'a [u8], CodecError> {
-        let fixed_len = self
-            .var_idx_offset
-            .checked_sub(Self::HEADER_LEN)
-            .ok_or(CodecError::InvalidLength)?;
-        let remaining = fixed_len
+        let bytes = self.peek_fixed(len)?;
+        // peek_fixed checked that `len` fits in the remaining FixedRegion.
+        self.fixed_cursor += len;
+        Ok(bytes)
+    }
+
+    /// Returns the next `len` bytes from the FixedRegion without advancing `fixed_cursor`.
+    pub fn peek_fixed(&self, len: u32) -> Result<&'a [u8], CodecError> {
+        let remaining = self
+            .fixed_len
             .checked_sub(self.fixed_cursor)
             .ok_or(CodecError::InvalidLength)?;
         if len > remaining {
-            return Err(CodecError::InvalidLength);
+            return Err(CodecError::Truncated {
+                needed: len as usize,
+                available: remaining as usize,
+            });
         }
 
         let start_abs = self
             .fixed_cursor
-            .checked_add(Self::HEADER_LEN)
+            .checked_add(self.fixed_offset)
             .ok_or(CodecError::InvalidLength)?;
         let end_abs = start_abs
             .checked_add(len)
@@ -133,10 +319,6 @@ impl<'a> Decoder<'a> {
         let start = usize::try_from(start_abs).map_err(|_| CodecError::InvalidLength)?;
         let end = usize::try_from(end_abs).map_err(|_| CodecError::InvalidLength)?;
 
-        self.fixed_cursor = self
-            .fixed_cursor
-            .checked_add(len)
-            .ok_or(CodecError::InvalidLength)?;
         Ok(&self.buf[start..end])
     }
 
@@ -144,19 +326,60 @@ impl<'a> Decoder<'a> {
     /// Each entry is a u32 offset; the slice is from entry[idx] to entry[idx+1] (or total_len for last).
     pub fn next_var(&mut self) -> Result<&'a [u8], CodecError> {
         let idx = self.next_var_index()?;
-        let count = self.var_count();
+        self.var_bytes(idx)
+    }
 
-        let start_abs = self.read_entry(idx)?;
-        let end_abs = if idx + 1 < count {
-            self.read_entry(idx + 1)?
-        } else {
-            self.total_len
-        };
+    /// Reads the next var segment as fixed-width `T` elements, decoding each one lazily.
+    ///
+    /// Segment-level errors (no var left, length not a multiple of `T::LENGTH`) are returned up
+    /// front; per-element decode errors are yielded by the iterator. Nothing is allocated, so e.g.
+    /// summing a `Vec<u32>` segment does not materialize it.
+    pub fn fixed_slice_iter<T: FixedDecode>(
+        &mut self,
+    ) -> Result<impl Iterator<Item = Result<T, CodecError>> + 'a, CodecError> {
+        let bytes = self.next_var()?;
+        // Zero-length elements carry no count, so only an empty segment is valid.
+        if T::LENGTH == 0 && !bytes.is_empty() {
+            return Err(CodecError::InvalidLength);
+        }
+        let len = T::LENGTH.max(1);
+        if !bytes.len().is_multiple_of(len) {
+            return Err(CodecError::InvalidLength);
+        }
+        let endian = self.config.endian;
+        Ok(bytes
+            .chunks_exact(len)
+            .map(move |chunk| T::decode(&chunk[..T::LENGTH], endian)))
+    }
 
-        // Bounds: start/end must be within data region and total_len.
-        if start_abs < self.data_offset || end_abs < start_abs || end_abs > self.total_len {
+    /// Reads the next var segment written by `Encoder::push_var_with_crc`, verifying its CRC-32.
+    ///
+    /// Returns the segment without the trailing CRC, or `CodecError::ValidationFailed` if the
+    /// checksum does not match.
+    pub fn next_var_verified(&mut self) -> Result<&'a [u8], CodecError> {
+        let segment = self.next_var()?;
+        let split = segment
+            .len()
+            .checked_sub(4)
+            .ok_or(CodecError::InvalidLength)?;
+        let (bytes, crc) = segment.split_at(split);
+        if read_u32_endian(crc, self.config.endian)? != crate::crc::crc32(bytes) {
+            return Err(CodecError::ValidationFailed);
+        }
+        Ok(bytes)
+    }
+
+    /// Returns the next variable-length value without advancing `var_cursor`.
+    pub fn peek_var(&self) -> Result<&'a [u8], CodecError> {
+        if self.var_cursor >= self.var_count() {
             return Err(CodecError::InvalidLength);
         }
+        self.var_bytes(self.var_cursor)
+    }
+
+    /// Returns the bytes of var segment `index`.
+    fn var_bytes(&self, index: u32) -> Result<&'a [u8], CodecError> {
+        let (start_abs, end_abs) = self.var_span(index)?;
 
         let start = usize::try_from(start_abs).map_err(|_| CodecError::InvalidLength)?;
         let end = usize::try_from(end_abs).map_err(|_| CodecError::InvalidLength)?;
@@ -167,15 +390,84 @@ impl<'a> Decoder<'a> {
         Ok(&self.buf[start..end])
     }
 
-    /// Reads the u32 at entry_idx from the variable-length index table (each entry is 4 bytes).
+    /// Returns every var segment in entry order without interpreting their types.
+    ///
+    /// Each segment ends at the next entry's offset, and the last one ends at `total_len`. The
+    /// var cursor is not moved.
+    pub fn all_var_segments(&self) -> Result<Vec<&'a [u8]>, CodecError> {
+        (0..self.var_count())
+            .map(|index| self.var_bytes(index))
+            .collect()
+    }
+
+    /// Returns the start offset stored in VarEntry `index`, relative to the start of `buf`.
+    pub fn var_entry_offset(&self, index: u32) -> Result<u32, CodecError> {
+        if index >= self.var_count() {
+            return Err(CodecError::InvalidLength);
+        }
+        self.read_entry(index)
+    }
+
+    /// Returns the `(start, end)` byte range of var segment `index`, relative to the start of `buf`.
+    ///
+    /// Callers may overwrite `buf[start..end]` in place to patch a segment. Only same-length
+    /// edits are valid: changing a segment's length shifts every later offset, so the payload
+    /// must be re-encoded instead.
+    pub fn var_span(&self, index: u32) -> Result<(u32, u32), CodecError> {
+        let start_abs = self.var_entry_offset(index)?;
+        // `var_count` (at most `u32::MAX / 4`) already bounds `index`; the checked successor is
+        // defensive only.
+        let next = index.checked_add(1).ok_or(CodecError::InvalidLength)?;
+        let end_abs = if next < self.var_count() {
+            self.read_entry(next)?
+        } else {
+            self.total_len
+        };
+
+        // Bounds: start/end must be within data region and total_len.
+        if start_abs < self.data_offset || end_abs < start_abs || end_abs > self.total_len {
+            return Err(CodecError::MalformedOffsets);
+        }
+
+        Ok((start_abs, end_abs))
+    }
+
+    /// Checks every stored VarEntry offset up front: each must lie in `[data_offset, total_len]`
+    /// and not precede the one before it.
+    ///
+    /// `Decoder::new` only checks the first and last entries, and later reads check each segment
+    /// as it is sliced. Call this to reject a payload whose entries point back into the header,
+    /// FixedRegion, or VarEntry region before decoding any of it. Returns
+    /// `CodecError::ValidationFailed` on the first bad entry.
+    pub fn validate_offsets(&self) -> Result<(), CodecError> {
+        let mut previous = self.data_offset;
+        for index in 0..self.var_count() {
+            let offset = self.read_entry(index)?;
+            if offset < previous || offset > self.total_len {
+                return Err(CodecError::ValidationFailed);
+            }
+            previous = offset;
+        }
+        Ok(())
+    }
+
+    /// Returns the length of var segment `index` from its VarEntry offsets, without reading it.
+    pub fn var_segment_len(&self, index: u32) -> Result<u32, CodecError> {
+        let (start_abs, end_abs) = self.var_span(index)?;
+        Ok(end_abs - start_abs)
+    }
+
+    /// Reads the u32 at entry_idx from the variable-length index table.
     fn read_entry(&self, entry_idx: u32) -> Result<u32, CodecError> {
-        let offset_in_entries = entry_idx.checked_mul(4).ok_or(CodecError::InvalidLength)?;
+        let offset_in_entries = entry_idx
+            .checked_mul(VAR_ENTRY_SIZE)
+            .ok_or(CodecError::InvalidLength)?;
         let var_entry_abs = self
             .var_idx_offset
             .checked_add(offset_in_entries)
             .ok_or(CodecError::InvalidLength)?;
         let var_entry_end_abs = var_entry_abs
-            .checked_add(4)
+            .checked_add(VAR_ENTRY_SIZE)
             .ok_or(CodecError::InvalidLength)?;
 
         if var_entry_end_abs > self.data_offset || var_entry_end_abs > self.total_len {
@@ -198,7 +490,7 @@ impl<'a> Decoder<'a> {
             return Err(CodecError::InvalidLength);
         }
         let idx = self.var_cursor;
-        self.var_cursor += 1;
+        self.var_cursor = idx.checked_add(1).ok_or(CodecError::InvalidLength)?;
         Ok(idx)
     }
 
@@ -208,3 +500,762 @@ impl<'a> Decoder<'a> {
         T::decode_field::<true>(self)
     }
 }
+
+/// Parses a payload with `Config::default()`; use `Decoder::new` for other configs.
+impl<'a> TryFrom<&'a [u8]> for Decoder<'a> {
+    type Error = CodecError;
+
+    fn try_from(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        Decoder::new(buf, Config::default())
+    }
+}
+
+/// Decoder that owns its payload buffer.
+///
+/// The header is validated once in `new`; `decoder` re-parses it on demand and hands out a
+/// fresh `Decoder` borrowing from the owned bytes.
+#[derive(Debug, Clone)]
+pub struct OwnedDecoder {
+    config: Config,
+    buf: Vec<u8>,
+}
+
+impl OwnedDecoder {
+    /// Takes ownership of `buf` and validates its header.
+    pub fn new(buf: Vec<u8>, config: Config) -> Result<Self, CodecError> {
+        Decoder::new(&buf, config.clone())?;
+        Ok(Self { config, buf })
+    }
+
+    /// Returns a fresh decoder positioned at the start of the owned payload.
+    pub fn decoder(&self) -> Decoder<'_> {
+        Decoder::new(&self.buf, self.config.clone()).expect("header validated in OwnedDecoder::new")
+    }
+
+    /// Returns the owned payload bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Gives back the owned payload buffer.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decoder, OwnedDecoder};
+    use crate::{CodecError, Config, Decode, Encode, Encoder};
+
+    #[test]
+    fn var_span_allows_in_place_segment_patch() {
+        let mut encoder = Encoder::new(Config::default());
+        let fixed_u8: u8 = 0x01;
+        let name: Vec<u8> = vec![0x61, 0x62, 0x63];
+        let values: Vec<u16> = vec![7, 8];
+        fixed_u8.encode_field::<false>(&mut encoder);
+        name.encode_field::<false>(&mut encoder);
+        values.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let (start, end) = decoder.var_span(0).expect("span");
+        assert_eq!(decoder.var_entry_offset(0), Ok(start));
+        assert_eq!(decoder.var_span(1).expect("span").0, end);
+        assert!(decoder.var_span(2).is_err());
+
+        out[start as usize..end as usize].copy_from_slice(b"xyz");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(u8::decode_field::<false>(&mut decoder), Ok(fixed_u8));
+        assert_eq!(
+            Vec::<u8>::decode_field::<false>(&mut decoder),
+            Ok(&b"xyz"[..])
+        );
+        assert_eq!(Vec::<u16>::decode_field::<true>(&mut decoder), Ok(values));
+    }
+
+    #[test]
+    fn fork_keeps_independent_cursors() {
+        let mut encoder = Encoder::new(Config::default());
+        let a: u16 = 0x0102;
+        let b: u32 = 0x0304_0506;
+        let first: Vec<u8> = vec![1, 2];
+        let second: Vec<u8> = vec![3];
+        a.encode_field::<false>(&mut encoder);
+        b.encode_field::<false>(&mut encoder);
+        first.encode_field::<false>(&mut encoder);
+        second.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(u16::decode_field::<false>(&mut decoder), Ok(a));
+
+        let mut fork = decoder.fork();
+        assert_eq!(u32::decode_field::<false>(&mut fork), Ok(b));
+        assert_eq!(Vec::<u8>::decode_field::<false>(&mut fork), Ok(&first[..]));
+        assert_eq!(Vec::<u8>::decode_field::<true>(&mut fork), Ok(&second[..]));
+        assert!(fork.next_var().is_err());
+
+        assert_eq!(decoder.fixed_cursor, 2);
+        assert_eq!(decoder.var_cursor, 0);
+        assert_eq!(u32::decode_field::<false>(&mut decoder), Ok(b));
+        assert_eq!(
+            Vec::<u8>::decode_field::<false>(&mut decoder),
+            Ok(&first[..])
+        );
+
+        decoder.rewind();
+        assert_eq!(u16::decode_field::<false>(&mut decoder), Ok(a));
+        assert_eq!(decoder.next_var(), Ok(&first[..]));
+    }
+
+    #[test]
+    fn expect_fixed_len_checks_fixed_region_size() {
+        let mut encoder = Encoder::new(Config::default());
+        let a: u16 = 1;
+        let b: [u8; 3] = [2, 3, 4];
+        let tail: Vec<u8> = vec![5];
+        a.encode_field::<false>(&mut encoder);
+        b.encode_field::<false>(&mut encoder);
+        tail.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+
+        assert_eq!(decoder.fixed_region_len(), 5);
+        assert_eq!(decoder.expect_fixed_len(5), Ok(()));
+        assert_eq!(
+            decoder.expect_fixed_len(4),
+            Err(CodecError::ValidationFailed)
+        );
+        assert_eq!(
+            decoder.expect_fixed_len(6),
+            Err(CodecError::ValidationFailed)
+        );
+    }
+
+    #[test]
+    fn peek_does_not_advance_cursors() {
+        let mut encoder = Encoder::new(Config::default());
+        let a: u32 = 0x0102_0304;
+        let first: Vec<u8> = vec![1, 2, 3];
+        let second: Vec<u8> = vec![4];
+        a.encode_field::<false>(&mut encoder);
+        first.encode_field::<false>(&mut encoder);
+        second.encode_field::<true>(&mut encoder);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+
+        let peeked_fixed = decoder.peek_fixed(4).expect("peek fixed");
+        assert_eq!(decoder.peek_fixed(4), Ok(peeked_fixed));
+        assert_eq!(decoder.fixed_cursor, 0);
+        assert_eq!(decoder.next_fixed_bytes(4), Ok(peeked_fixed));
+        assert!(decoder.peek_fixed(1).is_err());
+
+        let peeked_var = decoder.peek_var().expect("peek var");
+        assert_eq!(peeked_var, &first[..]);
+        assert_eq!(decoder.var_cursor, 0);
+        assert_eq!(decoder.next_var(), Ok(peeked_var));
+        assert_eq!(decoder.peek_var(), Ok(&second[..]));
+        assert_eq!(decoder.next_var(), Ok(&second[..]));
+        assert!(decoder.peek_var().is_err());
+    }
+
+    #[test]
+    fn next_var_verified_checks_segment_crc() {
+        let mut encoder = Encoder::new(Config::builder().big().build());
+        encoder.push_var_with_crc(b"blob");
+        encoder.push_var_with_crc(b"");
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let config = Config::builder().big().build();
+
+        let mut decoder = Decoder::new(&out, config.clone()).expect("decoder");
+        assert_eq!(decoder.next_var_verified(), Ok(&b"blob"[..]));
+        assert_eq!(decoder.next_var_verified(), Ok(&b""[..]));
+
+        let (start, _) = Decoder::new(&out, config.clone())
+            .expect("decoder")
+            .var_span(0)
+            .expect("span");
+        out[start as usize] ^= 0x01;
+        let mut decoder = Decoder::new(&out, config).expect("decoder");
+        assert_eq!(
+            decoder.next_var_verified(),
+            Err(CodecError::ValidationFailed)
+        );
+    }
+
+    #[test]
+    fn owned_decoder_decodes_from_moved_buffer() {
+        let mut encoder = Encoder::new(Config::default());
+        7u32.encode_field::<false>(&mut encoder);
+        vec![1u16, 2, 3].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let owned = OwnedDecoder::new(out, Config::default()).expect("owned decoder");
+        for _ in 0..2 {
+            let mut decoder = owned.decoder();
+            assert_eq!(u32::decode_field::<false>(&mut decoder), Ok(7));
+            assert_eq!(
+                <Vec<u16>>::decode_field::<true>(&mut decoder).expect("decode"),
+                vec![1, 2, 3]
+            );
+        }
+
+        assert_eq!(
+            OwnedDecoder::new(vec![0; 4], Config::default()).err(),
+            Some(CodecError::Truncated {
+                needed: 8,
+                available: 4
+            })
+        );
+    }
+
+    #[test]
+    fn fixed_align_pads_fixed_region_to_boundary() {
+        let config = Config::builder().fixed_align(16).build();
+        let mut encoder = Encoder::new(config.clone());
+        0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10u128.encode_field::<false>(&mut encoder);
+        vec![1u8, 2].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, config.clone()).expect("decoder");
+        assert_eq!(decoder.fixed_offset, 16);
+        assert_eq!(decoder.var_idx_offset, 32);
+        assert_eq!(out[8..12], 16u32.to_le_bytes());
+        assert!(out[12..16].iter().all(|&b| b == 0));
+        assert_eq!(
+            out[16..32],
+            0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10u128.to_le_bytes()
+        );
+
+        let mut decoder = Decoder::new(&out, config).expect("decoder");
+        assert_eq!(
+            u128::decode_field::<false>(&mut decoder),
+            Ok(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10)
+        );
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[1u8, 2][..])
+        );
+    }
+
+    #[test]
+    fn fixed_align_counts_magic_prefix_and_records_offset() {
+        let config = Config::builder().fixed_align(16).build();
+        let mut encoder = Encoder::new(config.clone());
+        0x0a0b_0c0du32.encode_field::<false>(&mut encoder);
+        vec![3u8].encode_field::<true>(&mut encoder);
+        encoder.push_trailer(b"tr");
+        let mut out = Vec::new();
+        encoder
+            .finalize_with_magic_version(&mut out)
+            .expect("finalize");
+
+        // The FixedRegion is aligned within `out`, past the five-byte magic+version prefix.
+        let prefix_len = config.magic.len() + 1;
+        let decoder = Decoder::new_with_magic_version(&out, config.clone()).expect("decoder");
+        assert_eq!((prefix_len + decoder.fixed_offset as usize) % 16, 0);
+        assert_eq!(decoder.trailer(), b"tr");
+
+        // The recorded offset is authoritative, so a decoder with another alignment still reads it.
+        let other = config.to_builder().fixed_align(4).build();
+        let mut decoder = Decoder::new_with_magic_version(&out, other).expect("decoder");
+        assert_eq!(u32::decode_field::<false>(&mut decoder), Ok(0x0a0b_0c0d));
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[3u8][..])
+        );
+    }
+
+    #[test]
+    fn fixed_align_rejects_bad_recorded_offset_or_padding() {
+        let config = Config::builder().fixed_align(16).build();
+        let mut encoder = Encoder::new(config.clone());
+        7u32.encode_field::<false>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut short = out.clone();
+        short[8..12].copy_from_slice(&4u32.to_le_bytes());
+        assert_eq!(
+            Decoder::new(&short, config.clone()).err(),
+            Some(CodecError::MalformedOffsets)
+        );
+
+        let mut dirty = out.clone();
+        dirty[13] = 1;
+        assert_eq!(
+            Decoder::new(&dirty, config.clone()).err(),
+            Some(CodecError::MalformedOffsets)
+        );
+
+        // An unpadded payload read with alignment enabled fails instead of shifting the fields.
+        let mut encoder = Encoder::new(Config::default());
+        0x0102_0304u32.encode_field::<false>(&mut encoder);
+        let mut plain = Vec::new();
+        encoder.finalize(&mut plain).expect("finalize");
+        assert_eq!(
+            Decoder::new(&plain, config).err(),
+            Some(CodecError::MalformedOffsets)
+        );
+    }
+
+    #[test]
+    fn payload_bytes_covers_total_len_and_excludes_trailer() {
+        let mut encoder = Encoder::new(Config::default());
+        9u32.encode_field::<false>(&mut encoder);
+        vec![1u8, 2, 3].encode_field::<true>(&mut encoder);
+        encoder.push_trailer(b"mac!");
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let payload = decoder.payload_bytes();
+        assert_eq!(payload.len(), decoder.total_len as usize);
+        assert_eq!(payload, &out[..out.len() - 4]);
+        assert_eq!(decoder.trailer(), b"mac!");
+
+        // A trailer length past the end of the buffer is truncation, not a shorter trailer.
+        assert_eq!(
+            Decoder::new(&out[..out.len() - 1], Config::default()).err(),
+            Some(CodecError::Truncated {
+                needed: out.len(),
+                available: out.len() - 1
+            })
+        );
+    }
+
+    #[test]
+    fn new_with_magic_version_checks_prefix() {
+        let config = Config::builder().magic(*b"test").version(3).build();
+        let mut encoder = Encoder::new(config.clone());
+        7u16.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder
+            .finalize_with_magic_version(&mut out)
+            .expect("finalize_with_magic_version");
+
+        let mut decoder = Decoder::new_with_magic_version(&out, config.clone()).expect("decoder");
+        assert_eq!(u16::decode_field::<true>(&mut decoder), Ok(7));
+
+        let other_magic = Config::builder().version(3).build();
+        assert_eq!(
+            Decoder::new_with_magic_version(&out, other_magic).err(),
+            Some(CodecError::ValidationFailed)
+        );
+        let other_version = Config::builder().magic(*b"test").version(4).build();
+        assert_eq!(
+            Decoder::new_with_magic_version(&out, other_version).err(),
+            Some(CodecError::ValidationFailed)
+        );
+        assert_eq!(
+            Decoder::new_with_magic_version(&out[..4], config).err(),
+            Some(CodecError::Truncated {
+                needed: 5,
+                available: 4
+            })
+        );
+    }
+
+    #[test]
+    fn empty_first_segment_keeps_data_offset_at_data_start() {
+        let mut encoder = Encoder::new(Config::default());
+        Vec::<u8>::new().encode_field::<false>(&mut encoder);
+        vec![1u8, 2, 3].encode_field::<false>(&mut encoder);
+        Vec::<u8>::new().encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.var_count(), 3);
+        assert_eq!(decoder.data_offset, 8 + 3 * 4);
+        assert_eq!(decoder.var_span(0), Ok((20, 20)));
+        assert_eq!(decoder.var_span(1), Ok((20, 23)));
+        assert_eq!(decoder.var_span(2), Ok((23, 23)));
+
+        // A last entry pointing past total_len means the inferred entry count is wrong.
+        let mut corrupt = out.clone();
+        corrupt[16..20].copy_from_slice(&24u32.to_le_bytes());
+        assert_eq!(
+            Decoder::new(&corrupt, Config::default()).err(),
+            Some(CodecError::MalformedOffsets)
+        );
+    }
+
+    #[test]
+    fn all_var_segments_slices_each_entry() {
+        let mut encoder = Encoder::new(Config::default());
+        9u8.encode_field::<false>(&mut encoder);
+        vec![1u8, 2, 3].encode_field::<false>(&mut encoder);
+        Vec::<u8>::new().encode_field::<false>(&mut encoder);
+        vec![0x0405u16].encode_field::<true>(&mut encoder);
+        encoder.push_trailer(b"tail");
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            decoder.all_var_segments(),
+            Ok(vec![&[1u8, 2, 3][..], &[][..], &[0x05, 0x04][..]])
+        );
+        assert_eq!(decoder.var_cursor, 0);
+    }
+
+    #[test]
+    fn fixed_only_payload_has_no_var_entries() {
+        let mut encoder = Encoder::new(Config::default());
+        0xaau8.encode_field::<false>(&mut encoder);
+        0x0102_0304u32.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.var_count(), 0);
+        assert_eq!(decoder.var_idx_offset, decoder.total_len);
+        assert_eq!(decoder.data_offset, decoder.total_len);
+        assert_eq!(decoder.all_var_segments(), Ok(vec![]));
+        assert_eq!(decoder.peek_var(), Err(CodecError::InvalidLength));
+        assert_eq!(decoder.next_var(), Err(CodecError::InvalidLength));
+        assert_eq!(decoder.var_cursor, 0);
+
+        // Fixed fields are still readable after the failed var reads.
+        assert_eq!(u8::decode_field::<false>(&mut decoder), Ok(0xaa));
+        assert_eq!(u32::decode_field::<true>(&mut decoder), Ok(0x0102_0304));
+    }
+
+    #[test]
+    fn try_from_slice_uses_default_config() -> Result<(), CodecError> {
+        let mut encoder = Encoder::default();
+        0x0102u16.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out)?;
+
+        let mut decoder = Decoder::try_from(&out[..])?;
+        assert_eq!(decoder.config(), &Config::default());
+        assert_eq!(u16::decode_field::<true>(&mut decoder)?, 0x0102);
+        assert_eq!(
+            Decoder::try_from(&out[..4]).err(),
+            Some(CodecError::Truncated {
+                needed: 8,
+                available: 4
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_with_eight_byte_magic() {
+        let config = Config::builder()
+            .magic_bytes(&b"PUFU\r\n\x1a\n"[..])
+            .version(9)
+            .build();
+        let mut encoder = Encoder::new(config.clone());
+        vec![1u8, 2].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder
+            .finalize_with_magic_version(&mut out)
+            .expect("finalize_with_magic_version");
+        assert_eq!(&out[..9], b"PUFU\r\n\x1a\n\x09");
+
+        let mut decoder = Decoder::new_with_magic_version(&out, config).expect("decoder");
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[1u8, 2][..])
+        );
+
+        let short = Config::builder().magic_bytes(&b"PU"[..]).version(9).build();
+        assert_eq!(
+            Decoder::new_with_magic_version(&out, short).err(),
+            Some(CodecError::ValidationFailed)
+        );
+    }
+
+    #[test]
+    fn decoder_distinguishes_truncated_from_malformed_offsets() {
+        let mut encoder = Encoder::new(Config::default());
+        1u32.encode_field::<false>(&mut encoder);
+        vec![1u8, 2].encode_field::<false>(&mut encoder);
+        vec![3u8].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        assert_eq!(out.len(), 23);
+
+        assert_eq!(
+            Decoder::new(&out[..20], Config::default()).err(),
+            Some(CodecError::Truncated {
+                needed: 23,
+                available: 20
+            })
+        );
+
+        // Swap the two VarEntry offsets so the table runs backwards.
+        let mut scrambled = out.clone();
+        scrambled[12..20].rotate_left(4);
+        let decoder = Decoder::new(&scrambled, Config::default());
+        assert_eq!(decoder.err(), Some(CodecError::MalformedOffsets));
+
+        // var_entry_offset before the end of the header.
+        let mut scrambled = out.clone();
+        scrambled[4..8].copy_from_slice(&4u32.to_le_bytes());
+        assert_eq!(
+            Decoder::new(&scrambled, Config::default()).err(),
+            Some(CodecError::MalformedOffsets)
+        );
+
+        assert_eq!(
+            CodecError::Truncated {
+                needed: 23,
+                available: 20
+            }
+            .to_string(),
+            "truncated input: needed 23 bytes, 20 available"
+        );
+    }
+
+    #[test]
+    fn decoder_rejects_var_idx_offset_near_u32_max() {
+        // `var_idx_offset + VAR_ENTRY_SIZE` would overflow a u32; the header checks reject the
+        // offset before that sum is formed, and the sum itself is checked.
+        let var_idx_offset = u32::MAX - 2;
+        for total_len in [16, u32::MAX] {
+            let mut buf = Vec::new();
+            buf.extend_from_slice(&total_len.to_le_bytes());
+            buf.extend_from_slice(&var_idx_offset.to_le_bytes());
+            buf.extend_from_slice(&[0u8; 8]);
+            let err = Decoder::new(&buf, Config::default()).err();
+            if total_len == u32::MAX {
+                assert_eq!(
+                    err,
+                    Some(CodecError::Truncated {
+                        needed: u32::MAX as usize,
+                        available: 16
+                    })
+                );
+            } else {
+                assert_eq!(err, Some(CodecError::MalformedOffsets));
+            }
+        }
+
+        let config = Config::builder().compact_header(true).build();
+        let mut buf = var_idx_offset.to_le_bytes().to_vec();
+        buf.extend_from_slice(&[0u8; 8]);
+        assert_eq!(
+            Decoder::new(&buf, config).err(),
+            Some(CodecError::MalformedOffsets)
+        );
+    }
+
+    #[test]
+    fn decoder_var_cursor_past_var_count_is_rejected() {
+        // The `var_count` bounds checks reject these before any index increment is attempted.
+        let mut encoder = Encoder::new(Config::default());
+        vec![1u8, 2].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        decoder.var_cursor = u32::MAX;
+
+        assert_eq!(decoder.peek_var().err(), Some(CodecError::InvalidLength));
+        assert_eq!(
+            decoder.next_var_index().err(),
+            Some(CodecError::InvalidLength)
+        );
+        assert_eq!(decoder.next_var().err(), Some(CodecError::InvalidLength));
+        assert_eq!(
+            decoder.var_span(u32::MAX).err(),
+            Some(CodecError::InvalidLength)
+        );
+        assert_eq!(decoder.var_cursor, u32::MAX);
+    }
+
+    #[test]
+    fn decoder_rejects_fixed_region_past_buffer() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&12u32.to_le_bytes());
+        buf.extend_from_slice(&64u32.to_le_bytes());
+        buf.extend_from_slice(&[0; 4]);
+        assert_eq!(
+            Decoder::new(&buf, Config::default()).err(),
+            Some(CodecError::MalformedOffsets)
+        );
+
+        buf[0..4].copy_from_slice(&64u32.to_le_bytes());
+        assert_eq!(
+            Decoder::new(&buf, Config::default()).err(),
+            Some(CodecError::Truncated {
+                needed: 64,
+                available: 12
+            })
+        );
+
+        buf[0..8].copy_from_slice(&[12, 0, 0, 0, 12, 0, 0, 0]);
+        let mut decoder = Decoder::new(&buf, Config::default()).expect("decoder");
+        assert_eq!(decoder.fixed_len, 4);
+        assert_eq!(decoder.next_fixed_bytes(4), Ok(&[0u8; 4][..]));
+        assert_eq!(
+            decoder.next_fixed_bytes(1).err(),
+            Some(CodecError::Truncated {
+                needed: 1,
+                available: 0
+            })
+        );
+    }
+
+    #[test]
+    fn decoder_var_segment_len_matches_encoded_lengths() {
+        let mut encoder = Encoder::new(Config::default());
+        vec![1u8, 2, 3].encode_field::<false>(&mut encoder);
+        Vec::<u8>::new().encode_field::<false>(&mut encoder);
+        vec![7u16, 8].encode_field::<true>(&mut encoder);
+        let lengths = encoder.var_lengths().to_vec();
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let decoded = (0..decoder.var_count())
+            .map(|index| decoder.var_segment_len(index))
+            .collect::<Result<Vec<_>, _>>();
+        assert_eq!(decoded, Ok(lengths));
+        assert_eq!(decoded, Ok(vec![3, 0, 4]));
+        assert_eq!(decoder.var_cursor, 0);
+        assert_eq!(
+            decoder.var_segment_len(3).err(),
+            Some(CodecError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn fixed_slice_iter_sums_without_materializing() {
+        let values: Vec<u32> = vec![1, 20, 300, 4000, 50_000];
+        let mut encoder = Encoder::new(Config::builder().big().build());
+        values.encode_field::<false>(&mut encoder);
+        vec![1u8, 2, 3].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        let eager = <Vec<u32>>::decode_field::<false>(&mut decoder.fork()).expect("eager");
+
+        let mut lazy = decoder.fork();
+        let sum = lazy
+            .fixed_slice_iter::<u32>()
+            .expect("segment")
+            .sum::<Result<u32, _>>();
+        assert_eq!(sum, Ok(eager.iter().sum()));
+        assert_eq!(lazy.var_cursor, 1);
+
+        // A non-empty segment cannot hold zero-length elements.
+        assert_eq!(
+            lazy.fork().fixed_slice_iter::<[u8; 0]>().err(),
+            Some(CodecError::InvalidLength)
+        );
+        // The three-byte segment is not a whole number of u16 elements.
+        assert_eq!(
+            lazy.fixed_slice_iter::<u16>().err(),
+            Some(CodecError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn tail_returns_bytes_after_the_payload() {
+        let mut out = Vec::new();
+        let mut encoder = Encoder::new(Config::default());
+        7u32.encode_field::<true>(&mut encoder);
+        encoder.finalize(&mut out).expect("finalize");
+        let payload_len = out.len();
+
+        let mut next = Encoder::new(Config::default());
+        8u32.encode_field::<true>(&mut next);
+        next.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.tail(), &out[payload_len..]);
+
+        let mut following = Decoder::new(decoder.tail(), Config::default()).expect("next frame");
+        assert_eq!(u32::decode_field::<true>(&mut following), Ok(8));
+        assert!(following.tail().is_empty());
+
+        // A recorded trailer belongs to its payload, so the tail starts after it.
+        let mut signed = Encoder::new(Config::default());
+        7u32.encode_field::<true>(&mut signed);
+        signed.push_trailer(b"sig");
+        let mut framed = Vec::new();
+        signed.finalize(&mut framed).expect("finalize");
+        let next_start = framed.len();
+        framed.extend_from_slice(&out[payload_len..]);
+
+        let decoder = Decoder::new(&framed, Config::default()).expect("decoder");
+        assert_eq!(decoder.trailer(), b"sig");
+        assert_eq!(decoder.tail(), &framed[next_start..]);
+    }
+
+    #[test]
+    fn restore_rewinds_to_saved_position() {
+        let mut encoder = Encoder::new(Config::default());
+        1u16.encode_field::<false>(&mut encoder);
+        2u32.encode_field::<false>(&mut encoder);
+        vec![3u8, 4].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(u16::decode_field::<false>(&mut decoder), Ok(1));
+        let saved = decoder.position();
+        assert_eq!(saved, (2, 0));
+
+        assert_eq!(u32::decode_field::<false>(&mut decoder), Ok(2));
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[3, 4][..])
+        );
+        assert_eq!(decoder.position(), (6, 1));
+
+        decoder.restore(saved).expect("restore");
+        assert_eq!(u32::decode_field::<false>(&mut decoder), Ok(2));
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[3, 4][..])
+        );
+
+        assert_eq!(decoder.restore((7, 0)), Err(CodecError::InvalidLength));
+        assert_eq!(decoder.restore((0, 2)), Err(CodecError::InvalidLength));
+        assert_eq!(decoder.position(), (6, 1));
+    }
+
+    #[test]
+    fn validate_offsets_rejects_entry_pointing_into_var_entries() {
+        let mut encoder = Encoder::new(Config::default());
+        vec![1u8].encode_field::<false>(&mut encoder);
+        vec![2u8].encode_field::<false>(&mut encoder);
+        vec![3u8].encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.validate_offsets(), Ok(()));
+
+        // Point the middle entry at the VarEntry region itself; the first and last entries that
+        // `Decoder::new` checks are untouched.
+        let mut scrambled = out.clone();
+        scrambled[12..16].copy_from_slice(&8u32.to_le_bytes());
+        let decoder = Decoder::new(&scrambled, Config::default()).expect("decoder");
+        assert_eq!(
+            decoder.validate_offsets(),
+            Err(CodecError::ValidationFailed)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Encoding support for pufu payloads.
 
-use crate::{DataMode, DataType, Encoder};
+use crate::{CodecError, Config, CountingEncoder, DataMode, DataType, Encoder};
 
 /// Encodes a single field into the provided encoder.
 pub trait Encode {
@@ -8,6 +8,25 @@ pub trait Encode {
     ///
     /// The const flag is used to enforce var2 layout constraints at compile time.
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder);
+
+    /// Count the bytes `encode_field` would write, without writing them.
+    ///
+    /// The default encodes into a scratch `Encoder` and counts its regions; implementations
+    /// override it to count directly.
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        let mut encoder = Encoder::new(counter.config().clone());
+        self.encode_field::<IS_LAST_VAR>(&mut encoder);
+        counter.add_encoder(&encoder);
+    }
+
+    /// Encode this value as a full top-level payload, including magic and version from `config`.
+    fn encode_to_vec_with(&self, config: &Config) -> Result<Vec<u8>, CodecError> {
+        let mut encoder = Encoder::new(config.clone());
+        self.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize_with_magic_version(&mut out)?;
+        Ok(out)
+    }
 }
 
 macro_rules! impl_field_encode_for_fixed_primitive {
@@ -17,13 +36,17 @@ macro_rules! impl_field_encode_for_fixed_primitive {
                 fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
                     self.push_fixed_data(&mut e.fixed, &e.config.endian);
                 }
+
+                fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+                    counter.add_fixed(<Self as DataType>::LENGTH);
+                }
             }
         )*
     };
 }
 
 impl_field_encode_for_fixed_primitive!(
-    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+    bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
 );
 
 impl<T, const N: usize> Encode for [T; N]
@@ -33,34 +56,125 @@ where
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
         self.push_fixed_data(&mut e.fixed, &e.config.endian);
     }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        counter.add_fixed(Self::LENGTH);
+    }
 }
 
-impl<T, const N: usize> Encode for &[T; N]
+impl<T> Encode for &T
 where
-    T: DataType,
+    T: Encode + ?Sized,
 {
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
-        self.push_fixed_data(&mut e.fixed, &e.config.endian);
+        (**self).encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        (**self).measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl<T> Encode for &mut T
+where
+    T: Encode + ?Sized,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        (**self).encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        (**self).measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+/// Tuples encode their elements in order, as if each were a separate field; only the last
+/// element inherits `IS_LAST_VAR`.
+impl<T0, T1> Encode for (T0, T1)
+where
+    T0: Encode,
+    T1: Encode,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.0.encode_field::<false>(e);
+        self.1.encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.0.measure_field::<false>(counter);
+        self.1.measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl<T0, T1, T2> Encode for (T0, T1, T2)
+where
+    T0: Encode,
+    T1: Encode,
+    T2: Encode,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.0.encode_field::<false>(e);
+        self.1.encode_field::<false>(e);
+        self.2.encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.0.measure_field::<false>(counter);
+        self.1.measure_field::<false>(counter);
+        self.2.measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl<T> Encode for std::num::Wrapping<T>
+where
+    T: Encode,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.0.encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.0.measure_field::<IS_LAST_VAR>(counter);
     }
 }
 
-impl<T, const N: usize> Encode for &mut [T; N]
+impl<T> Encode for std::cmp::Reverse<T>
+where
+    T: Encode,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.0.encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.0.measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl<T> Encode for std::ops::Range<T>
 where
     T: DataType,
 {
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
         self.push_fixed_data(&mut e.fixed, &e.config.endian);
     }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        counter.add_fixed(Self::LENGTH);
+    }
 }
 
-impl<T0, T1> Encode for (T0, T1)
+impl<T> Encode for std::ops::RangeInclusive<T>
 where
-    T0: DataType,
-    T1: DataType,
+    T: DataType,
 {
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
         self.push_fixed_data(&mut e.fixed, &e.config.endian);
     }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        counter.add_fixed(Self::LENGTH);
+    }
 }
 
 impl<T> Encode for Vec<T>
@@ -70,76 +184,179 @@ where
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
         match T::MODE {
             DataMode::Fixed => {
-                let mut length = 0;
-                for item in self.iter() {
-                    item.push_fixed_data(&mut e.data, &e.config.endian);
-                    length += T::LENGTH;
-                }
-                e.var_length.push(length as u32);
+                T::push_fixed_slice(self, &mut e.data, &e.config.endian);
+                e.push_fixed_segment_len::<T>(self.len());
             }
             DataMode::Var1 => {
                 if !IS_LAST_VAR {
                     panic!("var2 vectors cannot be encoded as last variable field");
                 }
                 for item in self.iter() {
-                    item.push_var1_data(&mut e.var_length, &mut e.data, &e.config.endian);
+                    item.push_var1_entry(&mut e.var_length, &mut e.data, &e.config);
                 }
             }
         }
     }
-}
 
-impl<T> Encode for &Vec<T>
-where
-    T: DataType,
-{
-    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
-        <Vec<T> as Encode>::encode_field::<IS_LAST_VAR>(self, e);
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        measure_elements::<T, IS_LAST_VAR>(self.len(), self.iter(), counter);
     }
 }
 
-impl<T> Encode for &mut Vec<T>
+impl<T> Encode for std::collections::VecDeque<T>
 where
     T: DataType,
 {
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
-        <Vec<T> as Encode>::encode_field::<IS_LAST_VAR>(self, e);
+        match T::MODE {
+            DataMode::Fixed => {
+                let (front, back) = self.as_slices();
+                T::push_fixed_slice(front, &mut e.data, &e.config.endian);
+                T::push_fixed_slice(back, &mut e.data, &e.config.endian);
+                e.push_fixed_segment_len::<T>(self.len());
+            }
+            DataMode::Var1 => {
+                if !IS_LAST_VAR {
+                    panic!("var2 vectors cannot be encoded as last variable field");
+                }
+                for item in self.iter() {
+                    item.push_var1_entry(&mut e.var_length, &mut e.data, &e.config);
+                }
+            }
+        }
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        measure_elements::<T, IS_LAST_VAR>(self.len(), self.iter(), counter);
     }
 }
 
-impl<T> Encode for &[T]
+impl<T> Encode for [T]
 where
     T: DataType,
 {
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
         match T::MODE {
             DataMode::Fixed => {
-                let mut length = 0;
-                for item in self.iter() {
-                    item.push_fixed_data(&mut e.data, &e.config.endian);
-                    length += T::LENGTH;
-                }
-                e.var_length.push(length as u32);
+                T::push_fixed_slice(self, &mut e.data, &e.config.endian);
+                e.push_fixed_segment_len::<T>(self.len());
             }
             DataMode::Var1 => {
                 if !IS_LAST_VAR {
                     panic!("var2 vectors cannot be encoded as last variable field");
                 }
                 for item in self.iter() {
-                    item.push_var1_data(&mut e.var_length, &mut e.data, &e.config.endian);
+                    item.push_var1_entry(&mut e.var_length, &mut e.data, &e.config);
                 }
             }
         }
     }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        measure_elements::<T, IS_LAST_VAR>(self.len(), self.iter(), counter);
+    }
+}
+
+/// Counts a `Vec<T>`-like field of `len` elements, mirroring its `encode_field`.
+fn measure_elements<'t, T, const IS_LAST_VAR: bool>(
+    len: usize,
+    items: impl Iterator<Item = &'t T>,
+    counter: &mut CountingEncoder,
+) where
+    T: DataType + 't,
+{
+    match T::MODE {
+        DataMode::Fixed => counter.add_fixed_segment::<T>(len),
+        DataMode::Var1 => {
+            if !IS_LAST_VAR {
+                panic!("var2 vectors cannot be encoded as last variable field");
+            }
+            for item in items {
+                item.measure_var1_entry(counter);
+            }
+        }
+    }
+}
+
+impl Encode for str {
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.as_bytes().encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.as_bytes().measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+/// C strings are written as one var segment that keeps the terminating nul.
+impl Encode for std::ffi::CStr {
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.to_bytes_with_nul().encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.to_bytes_with_nul()
+            .measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl Encode for std::ffi::CString {
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.as_c_str().encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.as_c_str().measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl<T> Encode for std::borrow::Cow<'_, T>
+where
+    T: Encode + ToOwned + ?Sized,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        (**self).encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        (**self).measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+/// Options write a presence byte (`0` or `1`) to the fixed region, followed by the inner value
+/// only when `Some`.
+impl<T> Encode for Option<T>
+where
+    T: Encode,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        match self {
+            Some(value) => {
+                1u8.encode_field::<false>(e);
+                value.encode_field::<IS_LAST_VAR>(e);
+            }
+            None => 0u8.encode_field::<false>(e),
+        }
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        counter.add_fixed(1);
+        if let Some(value) = self {
+            value.measure_field::<IS_LAST_VAR>(counter);
+        }
+    }
 }
 
-impl<T> Encode for &mut [T]
+impl<T> Encode for Box<T>
 where
-    T: DataType,
+    T: Encode + ?Sized,
 {
     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
-        let this: &[T] = self;
-        <&[T] as Encode>::encode_field::<IS_LAST_VAR>(&this, e);
+        (**self).encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        (**self).measure_field::<IS_LAST_VAR>(counter);
     }
 }
 
@@ -147,6 +364,22 @@ where
 mod tests {
     use crate::{Config, Encode, Encoder};
 
+    #[test]
+    fn encode_vec_u8_copies_bytes_in_bulk() {
+        let mut encoder = Encoder::new(Config::default());
+        let bytes: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        bytes.encode_field::<true>(&mut encoder);
+
+        assert_eq!(encoder.data, bytes);
+        assert_eq!(encoder.var_length, vec![1000]);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        assert_eq!(out[..4], 1012u32.to_le_bytes());
+        assert_eq!(out[8..12], 12u32.to_le_bytes());
+        assert_eq!(out[12..], bytes[..]);
+        // The speedup over a per-byte loop is measured by the `vec_u8_encode` bench.
+    }
+
     #[test]
     #[should_panic(expected = "var2 vectors cannot be encoded as last variable field")]
     fn rejects_var2_when_not_marked_last_var() {
@@ -154,4 +387,20 @@ mod tests {
         let value: Vec<Vec<u16>> = vec![vec![1, 2], vec![3]];
         value.encode_field::<false>(&mut encoder);
     }
+
+    #[test]
+    fn encode_slices_of_references_like_owned_values() {
+        let encode = |value: &dyn Fn(&mut Encoder)| {
+            let mut encoder = Encoder::new(Config::default());
+            value(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            out
+        };
+
+        let owned = encode(&|e| vec![1u32, 2, 3].encode_field::<true>(e));
+        let refs: &[&u32] = &[&1u32, &2, &3];
+        assert_eq!(encode(&|e| refs.encode_field::<true>(e)), owned);
+        assert_eq!(encode(&|e| refs.to_vec().encode_field::<true>(e)), owned);
+    }
 }
@@ -1,9 +1,10 @@
 //! Encoder for building binary payloads (see specs/0011-encoder.md).
 
-use crate::{CodecError, Config, Endian};
+use crate::layout::{fixed_region_offset_after, TRAILER_FLAG, TRAILER_LEN_SIZE, VAR_ENTRY_SIZE};
+use crate::{CodecError, Config, CountingEncoder, DataType, Encode, Endian};
 
 /// Writes `value` as 4 bytes into `out` using the given endianness (not serialized on wire).
-fn write_u32_endian(out: &mut Vec<u8>, value: u32, endian: Endian) {
+pub(crate) fn write_u32_endian(out: &mut Vec<u8>, value: u32, endian: Endian) {
     let bytes = match endian {
         Endian::Little => value.to_le_bytes(),
         Endian::Big => value.to_be_bytes(),
@@ -12,8 +13,26 @@ fn write_u32_endian(out: &mut Vec<u8>, value: u32, endian: Endian) {
     out.extend_from_slice(&bytes);
 }
 
+/// Snapshot of the Encoder region lengths, used to roll back speculative writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderCheckpoint {
+    /// Length of the fixed region at checkpoint time.
+    fixed_len: usize,
+    /// Number of variable-entry lengths at checkpoint time.
+    var_length_len: usize,
+    /// Length of the data region at checkpoint time.
+    data_len: usize,
+    /// Length of the trailer at checkpoint time.
+    trailer_len: usize,
+    /// Sticky overflow flag at checkpoint time.
+    overflowed: bool,
+}
+
 /// Encoder for building binary payloads. Holds Config (magic, version, endian); accumulates
 /// fixed region, variable-entry lengths, and data region.
+///
+/// Each region is appended to independently, so fixed values land in the FixedRegion in the
+/// order they are encoded, no matter how many var values are encoded between them.
 #[derive(Debug)]
 pub struct Encoder {
     /// Config for magic, version, and endianness (endian not serialized).
@@ -24,6 +43,10 @@ pub struct Encoder {
     pub(crate) var_length: Vec<u32>,
     /// Variable-length data region.
     pub(crate) data: Vec<u8>,
+    /// Uninterpreted bytes written after the payload; not counted in `total_len`.
+    pub(crate) trailer: Vec<u8>,
+    /// Sticky flag set once a var segment pushes the payload past `u32` offset space.
+    pub(crate) overflowed: bool,
 }
 
 impl Encoder {
@@ -34,6 +57,8 @@ impl Encoder {
             fixed: vec![],
             var_length: vec![],
             data: vec![],
+            trailer: vec![],
+            overflowed: false,
         }
     }
 
@@ -42,48 +67,248 @@ impl Encoder {
         &self.config
     }
 
-    /// Finalizes the payload into `out` (no magic or version). Uses config endian for u32 fields.
-    pub fn finalize(self, out: &mut Vec<u8>) -> Result<(), CodecError> {
-        const HEADER_FIELDS_LEN: u32 = 8;
+    /// Returns the byte order values are written in, with `Endian::Native` resolved to the host's.
+    pub fn endian(&self) -> Endian {
+        self.config.endian.resolve_native()
+    }
 
-        let fixed_len = u32::try_from(self.fixed.len()).map_err(|_| CodecError::InvalidLength)?;
-        let var_entry_len = self
-            .var_length
-            .len()
-            .checked_mul(4)
-            .and_then(|n| u32::try_from(n).ok())
-            .ok_or(CodecError::InvalidLength)?;
-        let data_len = u32::try_from(self.data.len()).map_err(|_| CodecError::InvalidLength)?;
+    /// Returns the FixedRegion bytes written so far.
+    pub fn fixed_bytes(&self) -> &[u8] {
+        &self.fixed
+    }
 
-        let total_len = HEADER_FIELDS_LEN
-            .checked_add(fixed_len)
-            .and_then(|n| n.checked_add(var_entry_len))
-            .and_then(|n| n.checked_add(data_len))
-            .ok_or(CodecError::InvalidLength)?;
-        let var_entry_offset = HEADER_FIELDS_LEN
+    /// Returns the recorded var segment lengths, in push order.
+    pub fn var_lengths(&self) -> &[u32] {
+        &self.var_length
+    }
+
+    /// Returns the data region bytes written so far.
+    pub fn data_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns an empty Encoder with this encoder's config but the given endianness.
+    ///
+    /// Byte order is applied when fields are encoded, so bytes already written cannot be
+    /// re-ordered at `finalize` time. To emit the same value in several byte orders, encode it
+    /// once into each encoder returned by this method.
+    pub fn clone_with_endian(&self, endian: Endian) -> Self {
+        Self::new(Config {
+            endian,
+            ..self.config.clone()
+        })
+    }
+
+    /// Captures the current region lengths so later writes can be undone with `rollback`.
+    pub fn checkpoint(&self) -> EncoderCheckpoint {
+        EncoderCheckpoint {
+            fixed_len: self.fixed.len(),
+            var_length_len: self.var_length.len(),
+            data_len: self.data.len(),
+            trailer_len: self.trailer.len(),
+            overflowed: self.overflowed,
+        }
+    }
+
+    /// Truncates all regions back to the lengths captured by `cp`; discarded bytes are not restored.
+    ///
+    /// Also restores the overflow flag, so an oversized speculative write can be abandoned.
+    pub fn rollback(&mut self, cp: EncoderCheckpoint) {
+        self.fixed.truncate(cp.fixed_len);
+        self.var_length.truncate(cp.var_length_len);
+        self.data.truncate(cp.data_len);
+        self.trailer.truncate(cp.trailer_len);
+        self.overflowed = cp.overflowed;
+    }
+
+    /// Appends a fixed-width value to the FixedRegion in config endianness.
+    pub fn push_fixed<T: DataType>(&mut self, value: &T) {
+        value.push_fixed_data(&mut self.fixed, &self.config.endian);
+    }
+
+    /// Appends `values` as one var segment of fixed-width elements, like a `Vec<T>` field.
+    pub fn push_var<T: DataType>(&mut self, values: &[T]) {
+        T::push_fixed_slice(values, &mut self.data, &self.config.endian);
+        self.push_fixed_segment_len::<T>(values.len());
+    }
+
+    /// Appends `bytes` followed by their CRC-32 as a single var segment.
+    ///
+    /// The CRC is written in config endianness; read it back with `Decoder::next_var_verified`.
+    pub fn push_var_with_crc(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+        write_u32_endian(&mut self.data, crate::crc::crc32(bytes), self.config.endian);
+        self.push_var_len(bytes.len() + 4);
+    }
+
+    /// Records a var segment of `count` fixed-width `T` elements already appended to `data`.
+    ///
+    /// The element count is only recoverable as `len / T::LENGTH`, so a non-empty segment of
+    /// zero-length elements (e.g. `Vec<[u8; 0]>`) cannot be encoded and panics.
+    pub(crate) fn push_fixed_segment_len<T: DataType>(&mut self, count: usize) {
+        if T::LENGTH == 0 && count != 0 {
+            panic!("vectors of zero-length fixed elements must be empty");
+        }
+        self.push_var_len(count.saturating_mul(T::LENGTH));
+    }
+
+    /// Records a var segment of `len` bytes that has already been appended to `data`.
+    ///
+    /// Checks incrementally that the payload still fits `u32` offsets; once it does not, the
+    /// encoder stays overflowed and `finalize` fails with `CodecError::InvalidLength`.
+    pub(crate) fn push_var_len(&mut self, len: usize) {
+        match u32::try_from(len) {
+            Ok(len) => self.var_length.push(len),
+            Err(_) => {
+                self.var_length.push(u32::MAX);
+                self.overflowed = true;
+            }
+        }
+        if self.region_lens_at(0).is_err() {
+            self.overflowed = true;
+        }
+    }
+
+    /// Returns true if the payload no longer fits `u32` offsets and `finalize` would fail.
+    pub fn would_overflow(&self) -> bool {
+        self.overflowed || self.region_lens_at(0).is_err()
+    }
+
+    /// Appends raw bytes to the trailer written after the data region.
+    ///
+    /// The trailer is excluded from `total_len`; a non-empty trailer sets `TRAILER_FLAG` in the
+    /// header and records its length in a `trailer_len` field, which `Decoder::trailer` reads.
+    pub fn push_trailer(&mut self, bytes: &[u8]) {
+        self.trailer.extend_from_slice(bytes);
+    }
+
+    /// Finalizes the payload into `out` (no magic or version). Uses config endian for u32 fields.
+    ///
+    /// The payload is appended: bytes already in `out` (e.g. a caller's own header) are kept, and
+    /// all offsets stay relative to the payload start, so it decodes with `Decoder::new_at`.
+    ///
+    /// On error `out` may hold a partial payload, so the result must be checked.
+    #[must_use = "on error `out` may hold a partial payload"]
+    pub fn finalize(self, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        if self.overflowed {
+            return Err(CodecError::InvalidLength);
+        }
+        // A compact header takes `total_len` from the buffer length, so trailing bytes would be
+        // read as part of the payload.
+        if self.config.compact_header && !self.trailer.is_empty() {
+            return Err(CodecError::InvalidLength);
+        }
+        // Align the FixedRegion within `out` itself, past any prefix already written to it.
+        let fixed_offset = self.fixed_offset_at(out.len())?;
+        let (fixed_len, var_entry_len, total_len) = self.region_lens(fixed_offset)?;
+        let var_entry_offset = fixed_offset
             .checked_add(fixed_len)
             .ok_or(CodecError::InvalidLength)?;
+        // The high bit of `var_entry_offset` is reserved for `TRAILER_FLAG`.
+        if var_entry_offset & TRAILER_FLAG != 0 {
+            return Err(CodecError::InvalidLength);
+        }
+        let trailer_len =
+            u32::try_from(self.trailer.len()).map_err(|_| CodecError::InvalidLength)?;
         let data_start_offset = var_entry_offset
             .checked_add(var_entry_len)
             .ok_or(CodecError::InvalidLength)?;
 
         let endian = self.config.endian;
-        write_u32_endian(out, total_len, endian);
-        write_u32_endian(out, var_entry_offset, endian);
+        if !self.config.compact_header {
+            write_u32_endian(out, total_len, endian);
+        }
+        if trailer_len == 0 {
+            write_u32_endian(out, var_entry_offset, endian);
+        } else {
+            write_u32_endian(out, var_entry_offset | TRAILER_FLAG, endian);
+            write_u32_endian(out, trailer_len, endian);
+        }
+        if self.config.records_fixed_offset() {
+            write_u32_endian(out, fixed_offset, endian);
+        }
+        out.resize(out.len() + (fixed_offset - self.header_len()) as usize, 0);
         out.extend_from_slice(&self.fixed);
 
         let mut current_data_offset = data_start_offset;
         for &length in &self.var_length {
+            // Offsets are monotonic by construction; each must still land inside the payload.
+            debug_assert!(
+                current_data_offset <= total_len,
+                "var entry offset {current_data_offset} exceeds total_len {total_len}"
+            );
             write_u32_endian(out, current_data_offset, endian);
             current_data_offset = current_data_offset
                 .checked_add(length)
                 .ok_or(CodecError::InvalidLength)?;
         }
+        // Header + fixed + var entries + sum(var_length) must account for every byte of total_len.
+        debug_assert_eq!(
+            current_data_offset, total_len,
+            "var_length entries must sum to the data region length"
+        );
         out.extend_from_slice(&self.data);
+        out.extend_from_slice(&self.trailer);
         Ok(())
     }
 
-    /// Writes full payload: 4-byte magic, 1-byte version from config, then layout as in `finalize`.
+    /// Returns the number of bytes `finalize` would append to an empty buffer, including the
+    /// trailer. With `fixed_align > 1` the padding, and so the length, depends on `out.len()`.
+    ///
+    /// Computed from the regions already encoded, without writing them into an output buffer.
+    pub fn encoded_len(&self) -> Result<usize, CodecError> {
+        if self.overflowed {
+            return Err(CodecError::InvalidLength);
+        }
+        let (_, _, total_len) = self.region_lens_at(0)?;
+        (total_len as usize)
+            .checked_add(self.trailer.len())
+            .ok_or(CodecError::InvalidLength)
+    }
+
+    /// Returns the header length, including the `trailer_len` field when a trailer was pushed.
+    fn header_len(&self) -> u32 {
+        if self.trailer.is_empty() {
+            self.config.header_len()
+        } else {
+            self.config.header_len() + TRAILER_LEN_SIZE
+        }
+    }
+
+    /// Returns the payload-relative FixedRegion start, including alignment padding, for a payload
+    /// written `base` bytes into its buffer.
+    fn fixed_offset_at(&self, base: usize) -> Result<u32, CodecError> {
+        fixed_region_offset_after(self.header_len(), self.config.fixed_align, base)
+            .ok_or(CodecError::InvalidLength)
+    }
+
+    /// Returns `region_lens` for a payload written `base` bytes into its buffer.
+    fn region_lens_at(&self, base: usize) -> Result<(u32, u32, u32), CodecError> {
+        self.region_lens(self.fixed_offset_at(base)?)
+    }
+
+    /// Returns `(fixed_len, var_entry_len, total_len)` for the current regions.
+    fn region_lens(&self, fixed_offset: u32) -> Result<(u32, u32, u32), CodecError> {
+        let fixed_len = u32::try_from(self.fixed.len()).map_err(|_| CodecError::InvalidLength)?;
+        let var_entry_len = self
+            .var_length
+            .len()
+            .checked_mul(VAR_ENTRY_SIZE as usize)
+            .and_then(|n| u32::try_from(n).ok())
+            .ok_or(CodecError::InvalidLength)?;
+        let data_len = u32::try_from(self.data.len()).map_err(|_| CodecError::InvalidLength)?;
+
+        let total_len = fixed_offset
+            .checked_add(fixed_len)
+            .and_then(|n| n.checked_add(var_entry_len))
+            .and_then(|n| n.checked_add(data_len))
+            .ok_or(CodecError::InvalidLength)?;
+        Ok((fixed_len, var_entry_len, total_len))
+    }
+
+    /// Writes full payload: magic (`config.magic.len()` bytes), 1-byte version from config, then
+    /// layout as in `finalize`.
+    #[must_use = "on error `out` may hold a partial payload"]
     pub fn finalize_with_magic_version(self, out: &mut Vec<u8>) -> Result<(), CodecError> {
         out.extend_from_slice(&self.config.magic);
         out.push(self.config.version);
@@ -91,10 +316,40 @@ impl Encoder {
     }
 }
 
+/// Returns the length of `value` encoded as a top-level payload with the given endianness.
+///
+/// Counts region lengths through `Encode::measure_field` with a `CountingEncoder`, so no data
+/// bytes are copied; types without a size-only path fall back to a scratch encode of themselves.
+pub fn encoded_len<T>(value: &T, endian: Endian) -> Result<usize, CodecError>
+where
+    T: Encode + ?Sized,
+{
+    let mut counter = CountingEncoder::new(Config::builder().endian(endian).build());
+    value.measure_field::<true>(&mut counter);
+    counter.encoded_len()
+}
+
+impl Default for Encoder {
+    /// Creates an Encoder with `Config::default()` (little-endian) and empty regions.
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Encoder;
-    use crate::{Config, Encode};
+    use super::{encoded_len, Encoder};
+    use crate::layout::{HEADER_LEN, TRAILER_FLAG};
+    use crate::{CodecError, Config, CountingEncoder, Decode, Decoder, Encode, Endian};
+
+    #[test]
+    fn default_encoder_is_little_endian_and_empty() {
+        let encoder = Encoder::default();
+        assert_eq!(encoder.config().endian, Endian::Little);
+        assert!(encoder.fixed.is_empty());
+        assert!(encoder.var_length.is_empty());
+        assert!(encoder.data.is_empty());
+    }
 
     #[test]
     fn encode_fixed_and_var1_vec_fixed() {
@@ -126,12 +381,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_fixed_fields_stay_adjacent_across_interleaved_var() {
+        let mut encoder = Encoder::new(Config::default());
+
+        0x11u8.encode_field::<false>(&mut encoder);
+        vec![0xa0u8, 0xa1].encode_field::<true>(&mut encoder);
+        0x22u8.encode_field::<false>(&mut encoder);
+
+        assert_eq!(encoder.fixed, vec![0x11, 0x22]);
+        assert_eq!(encoder.var_length, vec![2]);
+        assert_eq!(encoder.data, vec![0xa0, 0xa1]);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        assert_eq!(
+            &out[HEADER_LEN as usize..HEADER_LEN as usize + 2],
+            &[0x11, 0x22]
+        );
+    }
+
     #[test]
     fn encode_var2_vec_vec_fixed() {
         let mut encoder = Encoder::new(Config::default());
         let mut outer: Vec<Vec<u16>> = vec![vec![1, 2], vec![3]];
 
-        (&outer).encode_field::<true>(&mut encoder);
+        let outer_ref: &Vec<Vec<u16>> = &outer;
+        outer_ref.encode_field::<true>(&mut encoder);
         assert_eq!(encoder.var_length, vec![4, 2]);
         assert_eq!(encoder.data, vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]);
 
@@ -146,17 +422,121 @@ mod tests {
         );
 
         let mut encoder = Encoder::new(Config::default());
-        (&mut outer).encode_field::<true>(&mut encoder);
+        let outer_mut: &mut Vec<Vec<u16>> = &mut outer;
+        outer_mut.encode_field::<true>(&mut encoder);
         assert_eq!(encoder.var_length, vec![4, 2]);
         assert_eq!(encoder.data, vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]);
     }
 
     #[test]
-    #[should_panic(expected = "var1 vectors require fixed element types")]
-    fn rejects_var3_vec_vec_vec_u8() {
+    fn encode_var3_writes_each_middle_vector_as_nested_payload() {
         let mut encoder = Encoder::new(Config::default());
         let value: Vec<Vec<Vec<u8>>> = vec![vec![vec![1]]];
         value.encode_field::<true>(&mut encoder);
+
+        let mut nested = Encoder::new(Config::default());
+        value[0].encode_field::<true>(&mut nested);
+        let mut payload = Vec::new();
+        nested.finalize(&mut payload).expect("finalize");
+        assert_eq!(encoder.var_length, vec![payload.len() as u32]);
+        assert_eq!(encoder.data, payload);
+    }
+
+    #[test]
+    fn rollback_restores_checkpointed_regions() {
+        let mut encoder = Encoder::new(Config::default());
+        let fixed_u16: u16 = 0x0102;
+        let var_vec: Vec<u8> = vec![0x0a, 0x0b];
+        fixed_u16.encode_field::<false>(&mut encoder);
+        var_vec.encode_field::<false>(&mut encoder);
+
+        let cp = encoder.checkpoint();
+        let fixed = encoder.fixed.clone();
+        let var_length = encoder.var_length.clone();
+        let data = encoder.data.clone();
+
+        let extra_u32: u32 = 0x0304_0506;
+        let extra_vec: Vec<u16> = vec![1, 2, 3];
+        extra_u32.encode_field::<false>(&mut encoder);
+        extra_vec.encode_field::<true>(&mut encoder);
+        assert_ne!(encoder.fixed, fixed);
+
+        encoder.rollback(cp);
+        assert_eq!(encoder.fixed, fixed);
+        assert_eq!(encoder.var_length, var_length);
+        assert_eq!(encoder.data, data);
+        assert_eq!(encoder.checkpoint(), cp);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "var_length entries must sum to the data region length")]
+    fn finalize_debug_asserts_on_corrupted_var_length() {
+        let mut encoder = Encoder::new(Config::default());
+        let var_vec: Vec<u16> = vec![1, 2];
+        var_vec.encode_field::<true>(&mut encoder);
+        encoder.var_length[0] += 1;
+
+        let mut out = Vec::new();
+        let _ = encoder.finalize(&mut out);
+    }
+
+    #[test]
+    fn trailer_follows_payload_and_is_excluded_from_total_len() {
+        let mut encoder = Encoder::new(Config::default());
+        let fixed_u16: u16 = 0x0102;
+        let var_vec: Vec<u8> = vec![0x0a, 0x0b];
+        fixed_u16.encode_field::<false>(&mut encoder);
+        var_vec.encode_field::<true>(&mut encoder);
+        encoder.push_trailer(&[0xde, 0xad, 0xbe, 0xef]);
+
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        // The header flags the trailer and records its length after `var_entry_offset`.
+        assert_eq!(out[0..4], 20u32.to_le_bytes());
+        assert_eq!(out[4..8], (14 | TRAILER_FLAG).to_le_bytes());
+        assert_eq!(out[8..12], 4u32.to_le_bytes());
+        assert_eq!(out.len(), 24);
+
+        // A frame concatenated after the trailer is not part of it.
+        let mut next = Encoder::default();
+        7u32.encode_field::<true>(&mut next);
+        next.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.trailer(), &[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(u16::decode_field::<false>(&mut decoder), Ok(fixed_u16));
+        assert_eq!(
+            Vec::<u8>::decode_field::<true>(&mut decoder),
+            Ok(&var_vec[..])
+        );
+    }
+
+    #[test]
+    fn clone_with_endian_emits_both_byte_orders() {
+        let config = Config::builder().version(7).build();
+        let little = Encoder::new(config);
+        let big = little.clone_with_endian(Endian::Big);
+        assert_eq!(big.config().version, 7);
+        assert_eq!(big.config().endian, Endian::Big);
+
+        let value: Vec<u16> = vec![0x0102];
+        let mut outs = Vec::new();
+        for mut encoder in [little, big] {
+            value.encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            outs.push(out);
+        }
+
+        assert_eq!(
+            outs[0],
+            vec![14, 0, 0, 0, 8, 0, 0, 0, 12, 0, 0, 0, 0x02, 0x01]
+        );
+        assert_eq!(
+            outs[1],
+            vec![0, 0, 0, 14, 0, 0, 0, 8, 0, 0, 0, 12, 0x01, 0x02]
+        );
     }
 
     #[test]
@@ -176,7 +556,7 @@ mod tests {
             .expect("finalize_with_magic_version");
 
         assert!(out.starts_with(&[0x73, 0x76, 0x73, 0x64, 1]));
-        let body = &out[5..];
+        let body = &out[crate::layout::PREFIX_LEN..];
         assert_eq!(
             body,
             &[
@@ -203,4 +583,250 @@ mod tests {
 
         assert_eq!(out[0..5], [0xab, 0xcd, 0xef, 0x12, 42]);
     }
+
+    #[test]
+    fn encoded_len_matches_finalized_length() {
+        fn finalized_len<T: Encode + ?Sized>(value: &T, endian: Endian) -> usize {
+            let mut encoder = Encoder::new(Config::builder().endian(endian).build());
+            value.encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            out.len()
+        }
+
+        for endian in [Endian::Little, Endian::Big] {
+            assert_eq!(encoded_len(&7u64, endian), Ok(finalized_len(&7u64, endian)));
+            let bytes = vec![1u8, 2, 3];
+            assert_eq!(
+                encoded_len(&bytes, endian),
+                Ok(finalized_len(&bytes, endian))
+            );
+            let rows: Vec<Vec<u16>> = vec![vec![1, 2], vec![], vec![3]];
+            assert_eq!(encoded_len(&rows, endian), Ok(finalized_len(&rows, endian)));
+            assert_eq!(
+                encoded_len("text", endian),
+                Ok(finalized_len("text", endian))
+            );
+            let nested: Vec<Vec<Vec<u16>>> = vec![vec![vec![1], vec![]], vec![], vec![vec![2, 3]]];
+            assert_eq!(
+                encoded_len(&nested, endian),
+                Ok(finalized_len(&nested, endian))
+            );
+            let mixed = (Some(9u32), (None::<u8>, vec![(1u8, 2u16)]));
+            assert_eq!(
+                encoded_len(&mixed, endian),
+                Ok(finalized_len(&mixed, endian))
+            );
+            let rows: crate::Rows<u32> = [vec![1u32, 2], vec![3]].into_iter().collect();
+            assert_eq!(encoded_len(&rows, endian), Ok(finalized_len(&rows, endian)));
+        }
+
+        let mut counter = CountingEncoder::new(Config::default());
+        counter.add_var(u32::MAX as usize);
+        assert_eq!(counter.encoded_len(), Err(CodecError::InvalidLength));
+
+        let mut encoder = Encoder::default();
+        5u32.encode_field::<true>(&mut encoder);
+        encoder.push_trailer(b"tail");
+        let expected = encoder.encoded_len().expect("encoded_len");
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        assert_eq!(expected, out.len());
+    }
+
+    #[test]
+    fn oversized_var_segment_sets_sticky_overflow() {
+        let mut encoder = Encoder::default();
+        vec![1u8, 2].encode_field::<false>(&mut encoder);
+        assert!(!encoder.would_overflow());
+        let cp = encoder.checkpoint();
+
+        // Only the bookkeeping is exercised; the matching bytes would not fit in memory.
+        encoder.push_var_len(u32::MAX as usize + 1);
+        assert!(encoder.would_overflow());
+
+        // Truncating the regions by hand does not clear the sticky flag.
+        encoder.var_length.truncate(1);
+        assert!(encoder.would_overflow());
+        assert_eq!(encoder.encoded_len(), Err(CodecError::InvalidLength));
+
+        // Rolling back to a checkpoint taken before the overflow does.
+        encoder.rollback(cp);
+        assert!(!encoder.would_overflow());
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            <Vec<u8>>::decode_field::<false>(&mut decoder),
+            Ok(&[1u8, 2][..])
+        );
+    }
+
+    #[test]
+    fn accessors_expose_regions_before_finalize() {
+        let mut encoder = Encoder::default();
+        0x0102u16.encode_field::<false>(&mut encoder);
+        let rows: Vec<Vec<u16>> = vec![vec![1, 2], vec![], vec![3]];
+        rows.encode_field::<true>(&mut encoder);
+
+        assert_eq!(encoder.fixed_bytes(), &[0x02, 0x01]);
+        assert_eq!(encoder.var_lengths(), &[4, 0, 2]);
+        assert_eq!(encoder.data_bytes(), &[1, 0, 2, 0, 3, 0]);
+    }
+
+    #[test]
+    fn empty_encoder_produces_minimal_valid_payload() {
+        let mut out = Vec::new();
+        Encoder::new(Config::default())
+            .finalize(&mut out)
+            .expect("finalize");
+        assert_eq!(out, vec![8, 0, 0, 0, 8, 0, 0, 0]);
+        assert_eq!(Encoder::new(Config::default()).encoded_len(), Ok(8));
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(decoder.var_count(), 0);
+        assert_eq!(decoder.fixed_region_len(), 0);
+        assert_eq!(decoder.var_cursor, 0);
+        assert_eq!(decoder.fixed_cursor, 0);
+        assert!(decoder.trailer().is_empty());
+        assert_eq!(decoder.all_var_segments(), Ok(vec![]));
+        assert_eq!(decoder.peek_var().err(), Some(CodecError::InvalidLength));
+        assert!(decoder.next_fixed_bytes(0).expect("empty read").is_empty());
+        assert!(decoder.next_fixed_bytes(1).is_err());
+    }
+
+    #[test]
+    fn typed_push_helpers_build_a_decodable_payload() {
+        let mut manual = Encoder::new(Config::builder().big().build());
+        manual.push_fixed(&0xaau8);
+        manual.push_fixed(&[0x0102u16, 0x0304]);
+        manual.push_var(&[1u32, 2]);
+        manual.push_var::<u8>(&[]);
+        let mut out = Vec::new();
+        manual.finalize(&mut out).expect("finalize");
+
+        let mut derived = Encoder::new(Config::builder().big().build());
+        0xaau8.encode_field::<false>(&mut derived);
+        [0x0102u16, 0x0304].encode_field::<false>(&mut derived);
+        vec![1u32, 2].encode_field::<false>(&mut derived);
+        Vec::<u8>::new().encode_field::<true>(&mut derived);
+        let mut expected = Vec::new();
+        derived.finalize(&mut expected).expect("finalize");
+        assert_eq!(out, expected);
+
+        let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        assert_eq!(u8::decode_field::<false>(&mut decoder), Ok(0xaa));
+        assert_eq!(
+            <[u16; 2]>::decode_field::<false>(&mut decoder),
+            Ok([0x0102, 0x0304])
+        );
+        assert_eq!(
+            <Vec<u32>>::decode_field::<false>(&mut decoder),
+            Ok(vec![1, 2])
+        );
+        assert_eq!(<Vec<u8>>::decode_field::<true>(&mut decoder), Ok(&[][..]));
+    }
+
+    #[test]
+    fn compact_header_drops_total_len_and_roundtrips() {
+        let encode = |config: Config| {
+            let mut encoder = Encoder::new(config);
+            0x0102u16.encode_field::<false>(&mut encoder);
+            vec![7u8, 8, 9].encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            out
+        };
+        let compact = Config::builder().compact_header(true).build();
+
+        let full = encode(Config::default());
+        let short = encode(compact.clone());
+        assert_eq!(full.len() - short.len(), 4);
+        assert_eq!(short[..4], 6u32.to_le_bytes());
+
+        let mut decoder = Decoder::new(&short, compact.clone()).expect("decoder");
+        assert_eq!(decoder.fixed_region_len(), 2);
+        assert_eq!(decoder.payload_bytes(), short.as_slice());
+        assert_eq!(u16::decode_field::<false>(&mut decoder), Ok(0x0102));
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[7, 8, 9][..])
+        );
+
+        let aligned = compact.to_builder().fixed_align(8).build();
+        let padded = encode(aligned.clone());
+        assert_eq!(padded.len(), full.len());
+        let mut decoder = Decoder::new(&padded, aligned).expect("decoder");
+        assert_eq!(u16::decode_field::<false>(&mut decoder), Ok(0x0102));
+
+        let mut encoder = Encoder::new(compact);
+        encoder.push_trailer(&[0xff]);
+        let mut out = Vec::new();
+        assert_eq!(encoder.finalize(&mut out), Err(CodecError::InvalidLength));
+    }
+
+    #[test]
+    fn native_endian_roundtrips_and_matches_resolved_host_order() {
+        let encode = |config: Config| {
+            let mut encoder = Encoder::new(config);
+            0x0102_0304u32.encode_field::<false>(&mut encoder);
+            vec![0x0506u16, 0x0708].encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            out
+        };
+
+        let native = Config::builder().native().build();
+        let out = encode(native.clone());
+        assert_eq!(out, encode(Config::native()));
+        assert_eq!(Config::native().endian, Endian::Native.resolve_native());
+
+        let mut decoder = Decoder::new(&out, native).expect("decoder");
+        assert_eq!(u32::decode_field::<false>(&mut decoder), Ok(0x0102_0304));
+        assert_eq!(
+            <Vec<u16>>::decode_field::<true>(&mut decoder),
+            Ok(vec![0x0506, 0x0708])
+        );
+        assert_eq!(out[8..12], 0x0102_0304u32.to_ne_bytes());
+    }
+
+    #[test]
+    fn encoder_and_decoder_report_effective_endian() {
+        let encoder = Encoder::new(Config::builder().big().build());
+        assert_eq!(encoder.endian(), Endian::Big);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        let decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        assert_eq!(decoder.endian(), Endian::Big);
+
+        let native = Encoder::new(Config::builder().native().build());
+        assert_eq!(native.endian(), Endian::Native.resolve_native());
+        assert_ne!(native.endian(), Endian::Native);
+    }
+
+    #[test]
+    fn finalize_appends_after_existing_prefix() {
+        let mut out = b"HEAD!".to_vec();
+        let mut encoder = Encoder::new(Config::default());
+        7u16.encode_field::<false>(&mut encoder);
+        vec![1u8, 2, 3].encode_field::<true>(&mut encoder);
+        encoder.finalize(&mut out).expect("finalize");
+        assert_eq!(&out[..5], b"HEAD!");
+
+        let mut decoder = Decoder::new_at(&out, 5, Config::default()).expect("decoder");
+        assert_eq!(u16::decode_field::<false>(&mut decoder), Ok(7));
+        assert_eq!(
+            <Vec<u8>>::decode_field::<true>(&mut decoder),
+            Ok(&[1, 2, 3][..])
+        );
+        assert!(decoder.tail().is_empty());
+
+        assert_eq!(
+            Decoder::new_at(&out, out.len() + 1, Config::default()).err(),
+            Some(CodecError::Truncated {
+                needed: out.len() + 1,
+                available: out.len()
+            })
+        );
+    }
 }
@@ -0,0 +1,115 @@
+//! Endianness selection for fixed-width values.
+
+/// Endianness used when encoding/decoding fixed-width values.
+///
+/// **Not serialized**: Endian is never written to or read from the wire. It is only used
+/// at encode/decode time to control the byte order of multi-byte header and VarEntry fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    /// Little-endian byte order.
+    Little,
+    /// Big-endian byte order.
+    Big,
+    /// Byte order of the host, used for header, VarEntry, and data fields alike.
+    ///
+    /// Because endianness is not serialized, a `Native` payload only decodes correctly on a host
+    /// with the same byte order; exchanging it across hosts is unsupported. Use
+    /// `Endian::resolve_native` (or `Config::native`) to pin the host order explicitly.
+    Native,
+}
+
+impl Endian {
+    /// Returns the canonical lowercase name (`"little"`, `"big"`, or `"native"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Endian::Little => "little",
+            Endian::Big => "big",
+            Endian::Native => "native",
+        }
+    }
+
+    /// Resolves `Native` to `Little` or `Big` for the current host; other values are unchanged.
+    pub fn resolve_native(self) -> Self {
+        match self {
+            Endian::Native if cfg!(target_endian = "big") => Endian::Big,
+            Endian::Native => Endian::Little,
+            other => other,
+        }
+    }
+}
+
+impl std::fmt::Display for Endian {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing an unknown endianness name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEndianError {
+    input: String,
+}
+
+impl std::fmt::Display for ParseEndianError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown endianness `{}` (expected le/little, be/big, or ne/native)",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseEndianError {}
+
+impl std::str::FromStr for Endian {
+    type Err = ParseEndianError;
+
+    /// Parses `le`/`little`, `be`/`big`, or `ne`/`native`, ignoring ASCII case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "le" | "little" => Ok(Endian::Little),
+            "be" | "big" => Ok(Endian::Big),
+            "ne" | "native" => Ok(Endian::Native),
+            _ => Err(ParseEndianError {
+                input: s.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Endian;
+
+    #[test]
+    fn parses_endian_names_case_insensitively() {
+        assert_eq!("le".parse(), Ok(Endian::Little));
+        assert_eq!("Little".parse(), Ok(Endian::Little));
+        assert_eq!("BE".parse(), Ok(Endian::Big));
+        assert_eq!("big".parse(), Ok(Endian::Big));
+        assert_eq!("ne".parse(), Ok(Endian::Native));
+        assert_eq!("NATIVE".parse(), Ok(Endian::Native));
+        assert!("middle".parse::<Endian>().is_err());
+        assert!("".parse::<Endian>().is_err());
+    }
+
+    #[test]
+    fn as_str_roundtrips_through_from_str() {
+        for endian in [Endian::Little, Endian::Big, Endian::Native] {
+            assert_eq!(endian.as_str().parse(), Ok(endian));
+        }
+    }
+
+    #[test]
+    fn resolve_native_matches_host() {
+        let expected = if cfg!(target_endian = "big") {
+            Endian::Big
+        } else {
+            Endian::Little
+        };
+        assert_eq!(Endian::Native.resolve_native(), expected);
+        assert_eq!(Endian::Little.resolve_native(), Endian::Little);
+        assert_eq!(Endian::Big.resolve_native(), Endian::Big);
+    }
+}
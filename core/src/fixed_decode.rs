@@ -23,7 +23,7 @@ macro_rules! impl_fixed_decode_for_primitive {
                     Ok(match endian {
                         Endian::Little => <$t>::from_le_bytes(array),
                         Endian::Big => <$t>::from_be_bytes(array),
-                        Endian::Native => <$t>::from_le_bytes(array),
+                        Endian::Native => <$t>::from_ne_bytes(array),
                     })
                 }
             }
@@ -33,23 +33,44 @@ macro_rules! impl_fixed_decode_for_primitive {
 
 impl_fixed_decode_for_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 
+impl FixedDecode for bool {
+    const LENGTH: usize = 1;
+
+    /// Accepts only `0` and `1`; any other byte is `CodecError::ValidationFailed`.
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        let _ = endian;
+        match bytes {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [_] => Err(CodecError::ValidationFailed),
+            _ => Err(CodecError::InvalidLength),
+        }
+    }
+}
+
 impl<T, const N: usize> FixedDecode for [T; N]
 where
     T: FixedDecode,
 {
-    const LENGTH: usize = T::LENGTH * N;
+    const LENGTH: usize = crate::data_type::array_length(T::LENGTH, N);
 
     fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
         if T::LENGTH == 0 || !bytes.len().is_multiple_of(T::LENGTH) {
             return Err(CodecError::InvalidLength);
         }
+        let found = bytes.len() / T::LENGTH;
+        if found != N {
+            return Err(CodecError::ElementCountMismatch { expected: N, found });
+        }
 
         let mut items = Vec::with_capacity(N);
         for chunk in bytes.chunks_exact(T::LENGTH) {
             items.push(T::decode(chunk, endian)?);
         }
 
-        items.try_into().map_err(|_| CodecError::InvalidLength)
+        items
+            .try_into()
+            .map_err(|_| CodecError::ElementCountMismatch { expected: N, found })
     }
 }
 
@@ -61,7 +82,119 @@ where
     const LENGTH: usize = T0::LENGTH + T1::LENGTH;
 
     fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        if bytes.len() != Self::LENGTH {
+            return Err(CodecError::InvalidLength);
+        }
         let (t0, t1) = bytes.split_at(T0::LENGTH);
         Ok((T0::decode(t0, endian)?, T1::decode(t1, endian)?))
     }
 }
+
+impl<T0, T1, T2> FixedDecode for (T0, T1, T2)
+where
+    T0: FixedDecode,
+    T1: FixedDecode,
+    T2: FixedDecode,
+{
+    const LENGTH: usize = T0::LENGTH + T1::LENGTH + T2::LENGTH;
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        if bytes.len() != Self::LENGTH {
+            return Err(CodecError::InvalidLength);
+        }
+        let (t0, rest) = bytes.split_at(T0::LENGTH);
+        let (t1, t2) = rest.split_at(T1::LENGTH);
+        Ok((
+            T0::decode(t0, endian)?,
+            T1::decode(t1, endian)?,
+            T2::decode(t2, endian)?,
+        ))
+    }
+}
+
+impl<T> FixedDecode for std::num::Wrapping<T>
+where
+    T: FixedDecode,
+{
+    const LENGTH: usize = T::LENGTH;
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        T::decode(bytes, endian).map(std::num::Wrapping)
+    }
+}
+
+impl<T> FixedDecode for std::cmp::Reverse<T>
+where
+    T: FixedDecode,
+{
+    const LENGTH: usize = T::LENGTH;
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        T::decode(bytes, endian).map(std::cmp::Reverse)
+    }
+}
+
+impl<T> FixedDecode for std::ops::Range<T>
+where
+    T: FixedDecode,
+{
+    const LENGTH: usize = crate::data_type::array_length(T::LENGTH, 2);
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        let (start, end) = <(T, T)>::decode(bytes, endian)?;
+        Ok(start..end)
+    }
+}
+
+impl<T> FixedDecode for std::ops::RangeInclusive<T>
+where
+    T: FixedDecode,
+{
+    const LENGTH: usize = crate::data_type::array_length(T::LENGTH, 2);
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        let (start, end) = <(T, T)>::decode(bytes, endian)?;
+        Ok(start..=end)
+    }
+}
+
+impl<T> FixedDecode for Option<T>
+where
+    T: FixedDecode,
+{
+    const LENGTH: usize = 1 + T::LENGTH;
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        if bytes.len() != Self::LENGTH {
+            return Err(CodecError::InvalidLength);
+        }
+        match bytes[0] {
+            0 => Ok(None),
+            1 => T::decode(&bytes[1..], endian).map(Some),
+            _ => Err(CodecError::ValidationFailed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedDecode;
+    use crate::{CodecError, Endian};
+
+    #[test]
+    fn array_decode_reports_element_count_mismatch() {
+        let bytes = [0x01, 0x00, 0x02, 0x00];
+        assert_eq!(
+            <[u16; 3]>::decode(&bytes, Endian::Little),
+            Err(CodecError::ElementCountMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            <[u16; 3]>::decode(&bytes[..3], Endian::Little),
+            Err(CodecError::InvalidLength)
+        );
+        assert_eq!(<[u16; 2]>::decode(&bytes, Endian::Little), Ok([1, 2]));
+    }
+}
@@ -0,0 +1,200 @@
+//! Exact decimal values stored as scaled integers.
+
+use crate::{
+    CodecError, CountingEncoder, DataMode, DataType, Decode, Decoder, Encode, Encoder, Endian,
+    FixedDecode, NotU8,
+};
+
+/// Decimal with `SCALE` implied fractional digits, stored as a raw `i64` (e.g. cents when
+/// `SCALE == 2`).
+///
+/// Encodes exactly like its `i64`: eight fixed bytes in config endianness. Every raw value is
+/// valid, so decoding performs no extra validation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedPoint<const SCALE: u32>(i64);
+
+impl<const SCALE: u32> FixedPoint<SCALE> {
+    /// `10^SCALE`; evaluating it fails to compile when the factor does not fit in an `i64`.
+    const FACTOR: i64 = 10i64.pow(SCALE);
+
+    /// Wraps a raw scaled value, e.g. `FixedPoint::<2>::from_raw(12345)` is `123.45`.
+    pub const fn from_raw(raw: i64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the raw scaled value.
+    pub const fn raw(self) -> i64 {
+        self.0
+    }
+}
+
+impl<const SCALE: u32> std::fmt::Display for FixedPoint<SCALE> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let factor = Self::FACTOR.unsigned_abs();
+        let abs = self.0.unsigned_abs();
+        let sign = if self.0 < 0 { "-" } else { "" };
+        if SCALE == 0 {
+            return write!(f, "{sign}{abs}");
+        }
+        write!(
+            f,
+            "{sign}{}.{:0width$}",
+            abs / factor,
+            abs % factor,
+            width = SCALE as usize
+        )
+    }
+}
+
+/// Error returned when parsing a malformed or out-of-range decimal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFixedPointError {
+    input: String,
+}
+
+impl std::fmt::Display for ParseFixedPointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid fixed-point decimal `{}`", self.input)
+    }
+}
+
+impl std::error::Error for ParseFixedPointError {}
+
+impl<const SCALE: u32> std::str::FromStr for FixedPoint<SCALE> {
+    type Err = ParseFixedPointError;
+
+    /// Parses `[-]digits[.digits]` with at most `SCALE` fractional digits; missing fractional
+    /// digits are zero-filled.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseFixedPointError {
+            input: s.to_string(),
+        };
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if int.is_empty()
+            || !all_digits(int)
+            || !all_digits(frac)
+            || frac.len() > SCALE as usize
+            || (digits.contains('.') && frac.is_empty())
+        {
+            return Err(err());
+        }
+
+        // Accumulate the magnitude negatively so `i64::MIN` stays representable.
+        let mut raw: i64 = 0;
+        let padding = std::iter::repeat_n(b'0', SCALE as usize - frac.len());
+        for digit in int.bytes().chain(frac.bytes()).chain(padding) {
+            raw = raw
+                .checked_mul(10)
+                .and_then(|raw| raw.checked_sub(i64::from(digit - b'0')))
+                .ok_or_else(err)?;
+        }
+        if !negative {
+            raw = raw.checked_neg().ok_or_else(err)?;
+        }
+        Ok(Self(raw))
+    }
+}
+
+impl<const SCALE: u32> DataType for FixedPoint<SCALE> {
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = 8;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        self.0.push_fixed_data(encoder_fixed, endian);
+    }
+}
+
+impl<const SCALE: u32> FixedDecode for FixedPoint<SCALE> {
+    const LENGTH: usize = 8;
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        i64::decode(bytes, endian).map(Self)
+    }
+}
+
+impl<const SCALE: u32> NotU8 for FixedPoint<SCALE> {}
+
+impl<const SCALE: u32> Encode for FixedPoint<SCALE> {
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.0.encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.0.measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl<const SCALE: u32> Decode for FixedPoint<SCALE> {
+    type View<'a> = Self;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        i64::decode_field::<IS_LAST_VAR>(decoder).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedPoint;
+    use crate::{Config, Decode, Decoder, Encode, Encoder};
+
+    #[test]
+    fn roundtrip_fixed_point_as_eight_fixed_bytes() {
+        let price: FixedPoint<2> = "123.45".parse().expect("parse");
+        assert_eq!(price.raw(), 12345);
+        let prices = vec![FixedPoint::<2>::from_raw(-5), price];
+
+        let mut encoder = Encoder::new(Config::builder().big().build());
+        price.encode_field::<false>(&mut encoder);
+        prices.encode_field::<true>(&mut encoder);
+        assert_eq!(encoder.fixed_bytes(), 12345i64.to_be_bytes());
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        assert_eq!(
+            FixedPoint::<2>::decode_field::<false>(&mut decoder),
+            Ok(price)
+        );
+        assert_eq!(
+            <Vec<FixedPoint<2>>>::decode_field::<true>(&mut decoder),
+            Ok(prices)
+        );
+    }
+
+    #[test]
+    fn fixed_point_parses_and_formats_decimals() {
+        let cases = [
+            ("123.45", 12345, "123.45"),
+            ("-0.05", -5, "-0.05"),
+            ("7", 700, "7.00"),
+            ("7.5", 750, "7.50"),
+            ("-92233720368547758.08", i64::MIN, "-92233720368547758.08"),
+        ];
+        for (input, raw, formatted) in cases {
+            let value: FixedPoint<2> = input.parse().expect(input);
+            assert_eq!(value.raw(), raw, "{input}");
+            assert_eq!(value.to_string(), formatted);
+        }
+        assert_eq!(FixedPoint::<0>::from_raw(-42).to_string(), "-42");
+
+        for input in [
+            "",
+            "-",
+            "1.",
+            ".5",
+            "1.234",
+            "1e3",
+            "+1",
+            "92233720368547758.08",
+        ] {
+            assert!(input.parse::<FixedPoint<2>>().is_err(), "{input}");
+        }
+    }
+}
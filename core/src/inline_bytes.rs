@@ -0,0 +1,102 @@
+//! Short byte strings stored inline in the fixed region.
+
+use crate::{CodecError, CountingEncoder, Decode, Decoder, Encode, Encoder};
+
+/// Byte string encoded inline in the fixed region as a `u16` length followed by the bytes.
+///
+/// Avoids a VarEntry for small values; the length is limited to `u16::MAX` bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct InlineBytes(Vec<u8>);
+
+impl InlineBytes {
+    /// Wraps `bytes`, returning `CodecError::InvalidLength` if they exceed `u16::MAX` bytes.
+    pub fn new(bytes: Vec<u8>) -> Result<Self, CodecError> {
+        if bytes.len() > u16::MAX as usize {
+            return Err(CodecError::InvalidLength);
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Returns the wrapped bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Gives back the wrapped bytes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for InlineBytes {
+    type Error = CodecError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::new(bytes)
+    }
+}
+
+impl Encode for InlineBytes {
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        // `new` guarantees the length fits in a u16.
+        (self.0.len() as u16).encode_field::<false>(e);
+        e.fixed.extend_from_slice(&self.0);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        counter.add_fixed(2 + self.0.len());
+    }
+}
+
+impl Decode for InlineBytes {
+    type View<'a> = &'a [u8];
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        let _ = IS_LAST_VAR;
+        let len = u16::decode_field::<false>(decoder)?;
+        decoder.next_fixed_bytes(u32::from(len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InlineBytes;
+    use crate::{CodecError, Config, Decode, Decoder, Encode, Encoder};
+
+    #[test]
+    fn roundtrip_inline_bytes_in_fixed_region() {
+        let name = InlineBytes::new(b"inline".to_vec()).expect("inline");
+        let empty = InlineBytes::default();
+
+        let mut encoder = Encoder::new(Config::builder().big().build());
+        name.encode_field::<false>(&mut encoder);
+        empty.encode_field::<false>(&mut encoder);
+        7u8.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        assert_eq!(decoder.var_count(), 0);
+        assert_eq!(decoder.fixed_region_len(), 2 + 6 + 2 + 1);
+        assert_eq!(
+            InlineBytes::decode_field::<false>(&mut decoder),
+            Ok(&b"inline"[..])
+        );
+        assert_eq!(
+            InlineBytes::decode_field::<false>(&mut decoder),
+            Ok(&b""[..])
+        );
+        assert_eq!(u8::decode_field::<true>(&mut decoder), Ok(7));
+    }
+
+    #[test]
+    fn inline_bytes_rejects_values_longer_than_u16() {
+        assert!(InlineBytes::new(vec![0; u16::MAX as usize]).is_ok());
+        assert_eq!(
+            InlineBytes::try_from(vec![0; u16::MAX as usize + 1]),
+            Err(CodecError::InvalidLength)
+        );
+    }
+}
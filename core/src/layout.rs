@@ -0,0 +1,130 @@
+//! Wire layout constants (see specs/0010-binary-serde.md).
+//!
+//! A full payload is `[magic][version][total_len][var_entry_offset][FixedRegion][VarEntry][Data]`.
+//! `total_len`, `var_entry_offset`, and every VarEntry value are measured from the first byte of
+//! `total_len`, i.e. they exclude the magic+version prefix. With `Config::fixed_align > 1` the
+//! header also carries a `fixed_offset` field, followed by zero padding up to the FixedRegion.
+//! A payload with a trailer sets `TRAILER_FLAG` in `var_entry_offset` and carries a `trailer_len`
+//! field right after it.
+
+/// Length of the default magic identifier (`Config::magic` may be longer or shorter).
+pub const MAGIC_LEN: usize = 4;
+
+/// Length of the protocol version byte that follows the magic.
+pub const VERSION_LEN: usize = 1;
+
+/// Length of the magic+version prefix written by `Encoder::finalize_with_magic_version` with the
+/// default magic; in general it is `config.magic.len() + VERSION_LEN`.
+pub const PREFIX_LEN: usize = MAGIC_LEN + VERSION_LEN;
+
+/// Length of the header written by `Encoder::finalize` (`total_len` + `var_entry_offset`).
+///
+/// Invariant: `HEADER_LEN <= var_entry_offset <= total_len`.
+pub const HEADER_LEN: u32 = 8;
+
+/// Length of the header written when `Config::compact_header` is set (`var_entry_offset` only).
+///
+/// `total_len` is then implied by the buffer length, which saves four bytes per payload (and per
+/// nested struct) but means the payload must be delimited externally and cannot carry a trailer.
+pub const COMPACT_HEADER_LEN: u32 = 4;
+
+/// Length of the `fixed_offset` header field, present only when `Config::fixed_align > 1`.
+///
+/// It records the payload-relative FixedRegion start, so the decoder skips exactly the padding
+/// the encoder wrote.
+pub const FIXED_OFFSET_LEN: u32 = 4;
+
+/// Flag set in the high bit of `var_entry_offset` when the header carries a `trailer_len` field.
+///
+/// The `trailer_len` bytes written by `Encoder::push_trailer` follow the payload and are excluded
+/// from `total_len`. Decoders that predate the flag see an out-of-range `var_entry_offset` and
+/// reject the payload instead of misreading it.
+pub const TRAILER_FLAG: u32 = 1 << 31;
+
+/// Size of the `trailer_len` header field, present only when `TRAILER_FLAG` is set.
+pub const TRAILER_LEN_SIZE: u32 = 4;
+
+/// Size of one VarEntry slot (a `u32` offset).
+///
+/// Invariant: the VarEntry region length is a multiple of `VAR_ENTRY_SIZE`.
+pub const VAR_ENTRY_SIZE: u32 = 4;
+
+/// Offset of the FixedRegion for a full header written at an aligned position (e.g. the start
+/// of an empty buffer): the header, plus `FIXED_OFFSET_LEN` when `fixed_align > 1`, rounded up
+/// to `fixed_align` (see `Config::fixed_align`).
+///
+/// Returns `None` if the padded offset does not fit in a `u32`.
+pub fn fixed_region_offset(fixed_align: u32) -> Option<u32> {
+    let header_len = if fixed_align > 1 {
+        HEADER_LEN + FIXED_OFFSET_LEN
+    } else {
+        HEADER_LEN
+    };
+    fixed_region_offset_after(header_len, fixed_align, 0)
+}
+
+/// Payload-relative offset of the FixedRegion after a header of `header_len` bytes, padded so
+/// that it lands on a multiple of `fixed_align` when the payload starts `base` bytes into the
+/// written buffer.
+pub(crate) fn fixed_region_offset_after(
+    header_len: u32,
+    fixed_align: u32,
+    base: usize,
+) -> Option<u32> {
+    let align = u64::from(fixed_align.max(1));
+    let misalign = (base as u64).checked_add(u64::from(header_len))? % align;
+    let pad = (align - misalign) % align;
+    header_len.checked_add(u32::try_from(pad).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        fixed_region_offset, fixed_region_offset_after, HEADER_LEN, MAGIC_LEN, PREFIX_LEN,
+        VAR_ENTRY_SIZE,
+    };
+    use crate::{Config, Encode, Encoder};
+
+    #[test]
+    fn constants_match_emitted_layout() {
+        let mut out = Vec::new();
+        Encoder::new(Config::default())
+            .finalize(&mut out)
+            .expect("finalize");
+        assert_eq!(out.len(), HEADER_LEN as usize);
+
+        let mut out = Vec::new();
+        Encoder::new(Config::default())
+            .finalize_with_magic_version(&mut out)
+            .expect("finalize_with_magic_version");
+        assert_eq!(out.len(), PREFIX_LEN + HEADER_LEN as usize);
+        assert_eq!(out[..MAGIC_LEN], *Config::default().magic);
+
+        let mut encoder = Encoder::new(Config::default());
+        let empty: Vec<u8> = vec![];
+        empty.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+        assert_eq!(out.len(), (HEADER_LEN + VAR_ENTRY_SIZE) as usize);
+    }
+
+    #[test]
+    fn fixed_region_offset_pads_header_to_alignment() {
+        assert_eq!(fixed_region_offset(0), Some(HEADER_LEN));
+        assert_eq!(fixed_region_offset(1), Some(HEADER_LEN));
+        // Alignment adds the four-byte `fixed_offset` field before padding.
+        assert_eq!(fixed_region_offset(4), Some(12));
+        assert_eq!(fixed_region_offset(8), Some(16));
+        assert_eq!(fixed_region_offset(16), Some(16));
+        assert_eq!(fixed_region_offset(u32::MAX), Some(u32::MAX));
+    }
+
+    #[test]
+    fn fixed_region_offset_accounts_for_bytes_before_payload() {
+        // A five-byte magic+version prefix shifts the payload, so the padding absorbs it.
+        assert_eq!(fixed_region_offset_after(12, 16, 5), Some(27));
+        assert_eq!((5 + 27) % 16, 0);
+        assert_eq!(fixed_region_offset_after(12, 16, 4), Some(12));
+        assert_eq!(fixed_region_offset_after(8, 1, 3), Some(8));
+    }
+}
@@ -1,39 +1,55 @@
 //! pufu-core - Core library for pufu
 
+pub mod layout;
+
 mod encode;
 pub use encode::*;
 
 mod decode;
 pub use decode::*;
 
+mod inline_bytes;
+pub use inline_bytes::InlineBytes;
+
+mod fixed_point;
+pub use fixed_point::{FixedPoint, ParseFixedPointError};
+
+mod timestamp;
+pub use timestamp::Timestamp;
+
+mod rows;
+pub use rows::Rows;
+
 mod fixed_decode;
 pub use fixed_decode::FixedDecode;
 
 mod encoder;
-pub use encoder::Encoder;
+pub use encoder::{encoded_len, Encoder, EncoderCheckpoint};
+
+mod counting;
+pub use counting::CountingEncoder;
 
 mod decoder;
-pub use decoder::Decoder;
+pub use decoder::{Decoder, OwnedDecoder};
+
+mod stream;
+pub use stream::{StreamDecoder, StreamEncoder};
+
+mod crc;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+mod net;
 
 mod codec;
 pub use codec::CodecError;
 
 mod config;
-pub use config::{Config, ConfigBuilder};
+pub use config::{Config, ConfigBuilder, Magic};
 
 mod data_type;
 pub use data_type::*;
 
-/// Endianness used when encoding/decoding fixed-width values.
-///
-/// **Not serialized**: Endian is never written to or read from the wire. It is only used
-/// at encode/decode time to control the byte order of multi-byte header and VarEntry fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Endian {
-    /// Little-endian byte order.
-    Little,
-    /// Big-endian byte order.
-    Big,
-    /// Native endianness of the host (encoded as little-endian today).
-    Native,
-}
+mod endian;
+pub use endian::{Endian, ParseEndianError};
@@ -0,0 +1,163 @@
+//! Codecs for `core::net` IP addresses (re-exported as `std::net`).
+//!
+//! Addresses are written as their octets in network order regardless of `Endian`. `Ipv4Addr`
+//! and `Ipv6Addr` are fixed-width; `IpAddr` adds a one-byte tag (`4` or `6`) before the octets.
+
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::{
+    CodecError, CountingEncoder, DataMode, DataType, Decode, Decoder, Encode, Encoder, Endian,
+    FixedDecode, NotU8,
+};
+
+macro_rules! impl_ip_codec {
+    ($($t:ty => $len:expr),* $(,)?) => {
+        $(
+            impl DataType for $t {
+                const MODE: DataMode = DataMode::Fixed;
+                const LENGTH: usize = $len;
+
+                fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+                    let _ = endian;
+                    encoder_fixed.extend_from_slice(&self.octets());
+                }
+
+                fn push_var1_data(&self, var_length: &mut Vec<u32>, data: &mut Vec<u8>, endian: &Endian) {
+                    self.push_fixed_data(data, endian);
+                    var_length.push($len);
+                }
+            }
+
+            impl FixedDecode for $t {
+                const LENGTH: usize = $len;
+
+                fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+                    let _ = endian;
+                    let octets: [u8; $len] =
+                        bytes.try_into().map_err(|_| CodecError::InvalidLength)?;
+                    Ok(Self::from(octets))
+                }
+            }
+
+            impl NotU8 for $t {}
+
+            impl Encode for $t {
+                fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+                    self.push_fixed_data(&mut e.fixed, &e.config.endian);
+                }
+
+                fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+                    counter.add_fixed($len);
+                }
+            }
+
+            impl Decode for $t {
+                type View<'a> = $t;
+
+                fn decode_field<'a, const IS_LAST_VAR: bool>(
+                    decoder: &mut Decoder<'a>,
+                ) -> Result<Self::View<'a>, CodecError> {
+                    let _ = IS_LAST_VAR;
+                    let bytes = decoder.next_fixed_bytes($len)?;
+                    <$t as FixedDecode>::decode(bytes, decoder.config().endian)
+                }
+            }
+        )*
+    };
+}
+
+impl_ip_codec!(Ipv4Addr => 4, Ipv6Addr => 16);
+
+/// Tag byte written before an IPv4 address.
+const TAG_V4: u8 = 4;
+/// Tag byte written before an IPv6 address.
+const TAG_V6: u8 = 6;
+
+impl Encode for IpAddr {
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        match self {
+            IpAddr::V4(addr) => {
+                TAG_V4.encode_field::<false>(e);
+                addr.encode_field::<IS_LAST_VAR>(e);
+            }
+            IpAddr::V6(addr) => {
+                TAG_V6.encode_field::<false>(e);
+                addr.encode_field::<IS_LAST_VAR>(e);
+            }
+        }
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        counter.add_fixed(1);
+        match self {
+            IpAddr::V4(addr) => addr.measure_field::<IS_LAST_VAR>(counter),
+            IpAddr::V6(addr) => addr.measure_field::<IS_LAST_VAR>(counter),
+        }
+    }
+}
+
+impl Decode for IpAddr {
+    type View<'a> = IpAddr;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        match u8::decode_field::<false>(decoder)? {
+            TAG_V4 => Ipv4Addr::decode_field::<IS_LAST_VAR>(decoder).map(IpAddr::V4),
+            TAG_V6 => Ipv6Addr::decode_field::<IS_LAST_VAR>(decoder).map(IpAddr::V6),
+            _ => Err(CodecError::ValidationFailed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use crate::{CodecError, Config, Decode, Decoder, Encode, Encoder};
+
+    #[test]
+    fn roundtrip_ip_addrs_with_stable_tags() {
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2));
+        let mapped: Ipv6Addr = "::ffff:127.0.0.1".parse().expect("mapped");
+        let v6 = IpAddr::V6(mapped);
+        let peers = vec![Ipv4Addr::LOCALHOST, Ipv4Addr::BROADCAST];
+
+        let mut encoder = Encoder::new(Config::builder().big().build());
+        v4.encode_field::<false>(&mut encoder);
+        v6.encode_field::<false>(&mut encoder);
+        mapped.encode_field::<false>(&mut encoder);
+        peers.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        // Tags and octets are identical in both byte orders.
+        assert_eq!(out[8..13], [4, 192, 168, 1, 2]);
+        assert_eq!(out[13], 6);
+
+        let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        assert_eq!(IpAddr::decode_field::<false>(&mut decoder), Ok(v4));
+        // A v4-mapped address stays V6; nothing normalizes it to V4.
+        assert_eq!(IpAddr::decode_field::<false>(&mut decoder), Ok(v6));
+        assert_eq!(Ipv6Addr::decode_field::<false>(&mut decoder), Ok(mapped));
+        assert_eq!(
+            <Vec<Ipv4Addr>>::decode_field::<true>(&mut decoder),
+            Ok(peers)
+        );
+    }
+
+    #[test]
+    fn ip_addr_rejects_unknown_tag() {
+        let mut encoder = Encoder::new(Config::default());
+        5u8.encode_field::<false>(&mut encoder);
+        Ipv4Addr::LOCALHOST.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(
+            IpAddr::decode_field::<true>(&mut decoder),
+            Err(CodecError::ValidationFailed)
+        );
+    }
+}
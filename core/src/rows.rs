@@ -0,0 +1,207 @@
+//! Var2 tables decoded into a single backing buffer.
+
+use crate::decode::decode_fixed_slice_append;
+use crate::{
+    CodecError, CountingEncoder, DataMode, DataType, Decode, Decoder, Encode, Encoder, FixedDecode,
+};
+
+/// Rows of fixed-width elements stored back to back in one `Vec<T>`, with the row boundaries kept
+/// alongside.
+///
+/// Encodes exactly like `Vec<Vec<T>>` (one var entry per row, so it must be the last variable
+/// field), and the two decode each other's payloads. Decoding fills two buffers sized from the
+/// VarEntry table instead of allocating a `Vec` per row, which matters for tables of many small
+/// rows.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rows<T> {
+    items: Vec<T>,
+    ends: Vec<usize>,
+}
+
+impl<T> Rows<T> {
+    /// Creates an empty table.
+    pub const fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Appends a copy of `row` as the last row.
+    pub fn push_row(&mut self, row: &[T])
+    where
+        T: Clone,
+    {
+        self.items.extend_from_slice(row);
+        self.ends.push(self.items.len());
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns row `index`, or `None` if it is out of range.
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        let end = *self.ends.get(index)?;
+        let start = index.checked_sub(1).map_or(0, |prev| self.ends[prev]);
+        Some(&self.items[start..end])
+    }
+
+    /// Iterates over the rows in order.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    /// Returns every element of every row, flattened in row order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<T> Default for Rows<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, R> FromIterator<R> for Rows<T>
+where
+    T: Clone,
+    R: AsRef<[T]>,
+{
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut rows = Self::new();
+        for row in iter {
+            rows.push_row(row.as_ref());
+        }
+        rows
+    }
+}
+
+impl<T> Encode for Rows<T>
+where
+    T: DataType,
+{
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        if !IS_LAST_VAR {
+            panic!("var2 vectors cannot be encoded as last variable field");
+        }
+        if T::MODE != DataMode::Fixed {
+            panic!("var1 vectors require fixed element types");
+        }
+        for row in self.iter() {
+            T::push_fixed_slice(row, &mut e.data, &e.config.endian);
+            e.push_fixed_segment_len::<T>(row.len());
+        }
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        if !IS_LAST_VAR {
+            panic!("var2 vectors cannot be encoded as last variable field");
+        }
+        if T::MODE != DataMode::Fixed {
+            panic!("var1 vectors require fixed element types");
+        }
+        for row in self.iter() {
+            counter.add_fixed_segment::<T>(row.len());
+        }
+    }
+}
+
+impl<T> Decode for Rows<T>
+where
+    T: FixedDecode + 'static,
+{
+    type View<'a> = Rows<T>;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        if !IS_LAST_VAR {
+            return Err(CodecError::InvalidLength);
+        }
+
+        let first = decoder.var_cursor;
+        let count = decoder.var_count();
+        let mut rows = Rows::new();
+        if first >= count {
+            return Ok(rows);
+        }
+
+        // The remaining rows are contiguous in the Data region, so their total size bounds the
+        // element count before any row is read.
+        let (start, _) = decoder.var_span(first)?;
+        let (_, end) = decoder.var_span(count - 1)?;
+        let span = (end.saturating_sub(start)) as usize;
+        rows.items.reserve(span.checked_div(T::LENGTH).unwrap_or(0));
+        rows.ends.reserve((count - first) as usize);
+
+        while decoder.var_cursor < count {
+            let bytes = decoder.next_var()?;
+            decode_fixed_slice_append(bytes, decoder.config(), &mut rows.items)?;
+            rows.ends.push(rows.items.len());
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rows;
+    use crate::{CodecError, Config, Decode, Decoder, Encode, Encoder};
+
+    #[test]
+    fn rows_decode_large_table_into_one_buffer() {
+        let table: Vec<Vec<u16>> = (0..10_000u16)
+            .map(|row| (0..row % 5).map(|col| row.wrapping_mul(7) ^ col).collect())
+            .collect();
+
+        let mut encoder = Encoder::new(Config::default());
+        1u8.encode_field::<false>(&mut encoder);
+        table.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(u8::decode_field::<false>(&mut decoder), Ok(1));
+        let rows = Rows::<u16>::decode_field::<true>(&mut decoder).expect("rows");
+        assert_eq!(rows.len(), table.len());
+        assert!(rows.iter().eq(table.iter().map(Vec::as_slice)));
+        assert_eq!(
+            rows.items().len(),
+            table.iter().map(Vec::len).sum::<usize>()
+        );
+        assert_eq!(rows.get(table.len()), None);
+
+        // Rows re-encode to the same payload as the nested vectors they came from.
+        let mut encoder = Encoder::new(Config::default());
+        1u8.encode_field::<false>(&mut encoder);
+        rows.encode_field::<true>(&mut encoder);
+        let mut reencoded = Vec::new();
+        encoder.finalize(&mut reencoded).expect("finalize");
+        assert_eq!(reencoded, out);
+
+        decoder.rewind();
+        u8::decode_field::<false>(&mut decoder).expect("u8");
+        assert_eq!(
+            Rows::<u16>::decode_field::<false>(&mut decoder),
+            Err(CodecError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn rows_from_iterator_keeps_empty_rows() {
+        let rows: Rows<u32> = [vec![], vec![1, 2], vec![]].into_iter().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows.get(0), Some(&[][..]));
+        assert_eq!(rows.get(1), Some(&[1, 2][..]));
+        assert_eq!(rows.get(2), Some(&[][..]));
+        assert!(Rows::<u32>::new().is_empty());
+    }
+}
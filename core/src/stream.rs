@@ -0,0 +1,212 @@
+//! Count-prefixed streams of independent payloads.
+
+use crate::decoder::read_u32_endian;
+use crate::encoder::write_u32_endian;
+use crate::{CodecError, Config, Decoder, Encode, Encoder};
+
+/// Encodes a sequence of independent messages as `[count: u32][message]*`.
+///
+/// Each message is the output of `Encoder::finalize`, so it is self-delimiting through its
+/// `total_len` header field. The count uses the config endianness. Configs with
+/// `compact_header` set have no `total_len` and are rejected.
+#[derive(Debug)]
+pub struct StreamEncoder {
+    /// Config shared by every message in the stream.
+    config: Config,
+    /// Number of messages appended so far.
+    count: u32,
+    /// Finalized messages, back-to-back.
+    body: Vec<u8>,
+}
+
+impl StreamEncoder {
+    /// Creates an empty stream using `config` for every message.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            count: 0,
+            body: vec![],
+        }
+    }
+
+    /// Returns a fresh Encoder configured for the next message.
+    pub fn encoder(&self) -> Encoder {
+        Encoder::new(self.config.clone())
+    }
+
+    /// Finalizes `encoder` and appends it as the next message.
+    pub fn push(&mut self, encoder: Encoder) -> Result<(), CodecError> {
+        if self.config.compact_header {
+            return Err(CodecError::InvalidLength);
+        }
+        let count = self.count.checked_add(1).ok_or(CodecError::InvalidLength)?;
+        encoder.finalize(&mut self.body)?;
+        self.count = count;
+        Ok(())
+    }
+
+    /// Encodes `value` as a top-level field of a new message and appends it.
+    pub fn push_value<T: Encode>(&mut self, value: &T) -> Result<(), CodecError> {
+        let mut encoder = self.encoder();
+        value.encode_field::<true>(&mut encoder);
+        self.push(encoder)
+    }
+
+    /// Returns the number of messages appended so far.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Writes the count prefix followed by every message into `out`.
+    pub fn finish(self, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        write_u32_endian(out, self.count, self.config.endian);
+        out.extend_from_slice(&self.body);
+        Ok(())
+    }
+}
+
+/// Reads a stream written by `StreamEncoder`, yielding one `Decoder` per message.
+#[derive(Debug, Clone)]
+pub struct StreamDecoder<'a> {
+    /// Config used for every message in the stream.
+    config: Config,
+    /// Buffer containing the count prefix and messages.
+    buf: &'a [u8],
+    /// Byte offset of the next unread message.
+    offset: usize,
+    /// Number of messages not yet yielded.
+    remaining: u32,
+}
+
+impl<'a> StreamDecoder<'a> {
+    /// Parses the count prefix from `buf`. Fails for `compact_header` configs, whose messages
+    /// cannot be delimited.
+    pub fn new(buf: &'a [u8], config: Config) -> Result<Self, CodecError> {
+        if config.compact_header {
+            return Err(CodecError::InvalidLength);
+        }
+        let remaining = read_u32_endian(buf, config.endian)?;
+        Ok(Self {
+            config,
+            buf,
+            offset: 4,
+            remaining,
+        })
+    }
+
+    /// Returns the number of messages not yet yielded.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Returns a Decoder for the next message, or `None` once `count` messages were read.
+    pub fn next_message(&mut self) -> Option<Result<Decoder<'a>, CodecError>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.read_message())
+    }
+
+    /// Verifies that every counted message was read and no trailing bytes remain.
+    pub fn finish(self) -> Result<(), CodecError> {
+        if self.remaining != 0 || self.offset != self.buf.len() {
+            return Err(CodecError::InvalidLength);
+        }
+        Ok(())
+    }
+
+    /// Slices the message at `offset` using its `total_len` and `trailer_len` header fields and
+    /// advances past it.
+    fn read_message(&mut self) -> Result<Decoder<'a>, CodecError> {
+        let rest = self
+            .buf
+            .get(self.offset..)
+            .ok_or(CodecError::InvalidLength)?;
+        let mut decoder = Decoder::new(rest, self.config.clone()).map_err(|err| match err {
+            CodecError::Truncated { .. } => CodecError::InvalidLength,
+            err => err,
+        })?;
+        let len = decoder.frame_len();
+        decoder.buf = &rest[..len];
+        self.offset += len;
+        Ok(decoder)
+    }
+}
+
+impl<'a> Iterator for StreamDecoder<'a> {
+    type Item = Result<Decoder<'a>, CodecError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_message()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StreamDecoder, StreamEncoder};
+    use crate::{CodecError, Config, Decode, Encode};
+
+    #[test]
+    fn stream_roundtrips_exact_message_count() {
+        let mut stream = StreamEncoder::new(Config::default());
+        for i in 0..5u16 {
+            let payload: Vec<u16> = (0..i).collect();
+            stream.push_value(&payload).expect("push");
+        }
+        assert_eq!(stream.count(), 5);
+
+        let mut out = Vec::new();
+        stream.finish(&mut out).expect("finish");
+
+        let mut decoder = StreamDecoder::new(&out, Config::default()).expect("stream");
+        assert_eq!(decoder.remaining(), 5);
+        let mut decoded = Vec::new();
+        for message in decoder.by_ref() {
+            let mut message = message.expect("message");
+            decoded.push(Vec::<u16>::decode_field::<true>(&mut message).expect("payload"));
+        }
+        assert_eq!(decoded.len(), 5);
+        for (i, payload) in decoded.iter().enumerate() {
+            assert_eq!(*payload, (0..i as u16).collect::<Vec<_>>());
+        }
+        decoder.finish().expect("finish");
+    }
+
+    #[test]
+    fn stream_keeps_message_trailers_out_of_the_next_message() {
+        let mut stream = StreamEncoder::new(Config::default());
+        let mut encoder = stream.encoder();
+        vec![1u16, 2].encode_field::<true>(&mut encoder);
+        encoder.push_trailer(b"sig!");
+        stream.push(encoder).expect("push");
+        stream.push_value(&vec![3u16]).expect("push");
+        let mut out = Vec::new();
+        stream.finish(&mut out).expect("finish");
+
+        let mut decoder = StreamDecoder::new(&out, Config::default()).expect("stream");
+        let mut first = decoder.next_message().expect("first").expect("message");
+        assert_eq!(first.trailer(), b"sig!");
+        assert_eq!(Vec::<u16>::decode_field::<true>(&mut first), Ok(vec![1, 2]));
+        let mut second = decoder.next_message().expect("second").expect("message");
+        assert!(second.trailer().is_empty());
+        assert_eq!(Vec::<u16>::decode_field::<true>(&mut second), Ok(vec![3]));
+        decoder.finish().expect("finish");
+    }
+
+    #[test]
+    fn stream_rejects_count_beyond_messages() {
+        let mut stream = StreamEncoder::new(Config::default());
+        stream.push_value(&1u32).expect("push");
+        let mut out = Vec::new();
+        stream.finish(&mut out).expect("finish");
+        out[0] = 2;
+
+        let mut decoder = StreamDecoder::new(&out, Config::default()).expect("stream");
+        assert!(decoder.next_message().expect("first").is_ok());
+        assert_eq!(
+            decoder.next_message().map(|m| m.map(|_| ())),
+            Some(Err(CodecError::InvalidLength))
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Round-trip assertions for downstream test suites (enabled by the `test-util` feature).
+
+use std::fmt::Debug;
+
+use crate::{Config, Decode, Decoder, Encode, Encoder, Endian};
+
+/// Encodes `value` as a top-level payload with `endian`, decodes it back, and asserts that the
+/// decoded view equals `value` and that the whole payload was consumed.
+///
+/// Useful for checking hand-written `Encode`/`Decode` impls:
+///
+/// ```
+/// use pufu_core::test_util::assert_roundtrip;
+/// use pufu_core::{CodecError, Decode, Decoder, Encode, Encoder, Endian};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Celsius(i16);
+///
+/// impl Encode for Celsius {
+///     fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+///         e.push_fixed(&self.0);
+///     }
+/// }
+///
+/// impl Decode for Celsius {
+///     type View<'a> = Celsius;
+///
+///     fn decode_field<'a, const IS_LAST_VAR: bool>(
+///         decoder: &mut Decoder<'a>,
+///     ) -> Result<Self::View<'a>, CodecError> {
+///         i16::decode_field::<IS_LAST_VAR>(decoder).map(Celsius)
+///     }
+/// }
+///
+/// assert_roundtrip(&Celsius(-40), Endian::Big);
+/// ```
+///
+/// # Panics
+///
+/// Panics if encoding or decoding fails, if bytes are left unread, or if the view differs.
+pub fn assert_roundtrip<T>(value: &T, endian: Endian)
+where
+    T: Encode + Decode + Debug,
+    for<'a> T::View<'a>: PartialEq<T> + Debug,
+{
+    let config = Config::builder().endian(endian).build();
+    let mut encoder = Encoder::new(config.clone());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder
+        .finalize(&mut out)
+        .unwrap_or_else(|e| panic!("finalize {value:?}: {e}"));
+
+    let mut decoder =
+        Decoder::new(&out, config).unwrap_or_else(|e| panic!("decoder for {value:?}: {e}"));
+    let view =
+        T::decode_field::<true>(&mut decoder).unwrap_or_else(|e| panic!("decode {value:?}: {e}"));
+    assert_eq!(view, *value, "roundtrip mismatch ({endian} endian)");
+    assert_eq!(
+        decoder.fixed_cursor,
+        decoder.fixed_region_len(),
+        "fixed region not fully consumed for {value:?}"
+    );
+    assert_eq!(
+        decoder.var_cursor,
+        decoder.var_count(),
+        "var entries not fully consumed for {value:?}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::assert_roundtrip;
+    use crate::{CodecError, Decode, Decoder, Encode, Encoder, Endian, FixedPoint};
+
+    #[test]
+    fn assert_roundtrip_accepts_borrowed_and_owned_views() {
+        for endian in [Endian::Little, Endian::Big, Endian::Native] {
+            assert_roundtrip(&0x0102_0304u32, endian);
+            assert_roundtrip(&vec![1u8, 2, 3], endian);
+            assert_roundtrip(&vec![1u16, u16::MAX], endian);
+            assert_roundtrip(&FixedPoint::<2>::from_raw(12345), endian);
+        }
+    }
+
+    /// Writes a trailing fixed byte that its `Decode` impl never reads.
+    #[derive(Debug, PartialEq)]
+    struct Sloppy(u8);
+
+    impl Encode for Sloppy {
+        fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+            e.push_fixed(&self.0);
+            e.push_fixed(&0u8);
+        }
+    }
+
+    impl Decode for Sloppy {
+        type View<'a> = Sloppy;
+
+        fn decode_field<'a, const IS_LAST_VAR: bool>(
+            decoder: &mut Decoder<'a>,
+        ) -> Result<Self::View<'a>, CodecError> {
+            u8::decode_field::<IS_LAST_VAR>(decoder).map(Sloppy)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "fixed region not fully consumed")]
+    fn assert_roundtrip_rejects_unread_bytes() {
+        assert_roundtrip(&Sloppy(1), Endian::Little);
+    }
+}
@@ -0,0 +1,103 @@
+//! Wall-clock timestamps as milliseconds since the Unix epoch.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    CodecError, CountingEncoder, DataMode, DataType, Decode, Decoder, Encode, Encoder, Endian,
+    FixedDecode, NotU8,
+};
+
+/// Milliseconds since the Unix epoch, stored as a raw `u64`.
+///
+/// Encodes exactly like its `u64`: eight fixed bytes in config endianness. `Instant` has no
+/// stable epoch and cannot be serialized, so messages carry this instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// Wraps a millisecond count since the Unix epoch.
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis)
+    }
+
+    /// Returns the millisecond count since the Unix epoch.
+    pub const fn as_millis(self) -> u64 {
+        self.0
+    }
+
+    /// Reads the system clock. Times before the epoch clamp to `0`, and times past
+    /// `u64::MAX` milliseconds clamp to `u64::MAX`.
+    pub fn now() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        Self(u64::try_from(millis).unwrap_or(u64::MAX))
+    }
+}
+
+impl DataType for Timestamp {
+    const MODE: DataMode = DataMode::Fixed;
+    const LENGTH: usize = 8;
+
+    fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &Endian) {
+        self.0.push_fixed_data(encoder_fixed, endian);
+    }
+}
+
+impl FixedDecode for Timestamp {
+    const LENGTH: usize = 8;
+
+    fn decode(bytes: &[u8], endian: Endian) -> Result<Self, CodecError> {
+        u64::decode(bytes, endian).map(Self)
+    }
+}
+
+impl NotU8 for Timestamp {}
+
+impl Encode for Timestamp {
+    fn encode_field<const IS_LAST_VAR: bool>(&self, e: &mut Encoder) {
+        self.0.encode_field::<IS_LAST_VAR>(e);
+    }
+
+    fn measure_field<const IS_LAST_VAR: bool>(&self, counter: &mut CountingEncoder) {
+        self.0.measure_field::<IS_LAST_VAR>(counter);
+    }
+}
+
+impl Decode for Timestamp {
+    type View<'a> = Self;
+
+    fn decode_field<'a, const IS_LAST_VAR: bool>(
+        decoder: &mut Decoder<'a>,
+    ) -> Result<Self::View<'a>, CodecError> {
+        u64::decode_field::<IS_LAST_VAR>(decoder).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+    use crate::{Config, Decode, Decoder, Encode, Encoder};
+
+    #[test]
+    fn roundtrip_timestamp_as_eight_fixed_bytes() {
+        let sent = Timestamp::from_millis(1_700_000_000_123);
+        let history = vec![Timestamp::from_millis(0), Timestamp::now()];
+        assert!(history[1] > sent);
+
+        let mut encoder = Encoder::new(Config::builder().big().build());
+        sent.encode_field::<false>(&mut encoder);
+        history.encode_field::<true>(&mut encoder);
+        assert_eq!(encoder.fixed_bytes(), sent.as_millis().to_be_bytes());
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+        assert_eq!(Timestamp::decode_field::<false>(&mut decoder), Ok(sent));
+        assert_eq!(
+            <Vec<Timestamp>>::decode_field::<true>(&mut decoder),
+            Ok(history)
+        );
+    }
+}
@@ -14,10 +14,199 @@ pub struct FieldSpec<'a> {
     pub field_vis: Vec<&'a syn::Visibility>,
     /// Flags marking which fields are last-variable entries.
     pub field_flags: Vec<proc_macro2::TokenStream>,
+    /// Declaration indices in the order fields are encoded/decoded.
+    pub order: Vec<usize>,
+    /// Fields encoded as one nested payload in a single var entry: var2 fields that are not the
+    /// last variable field, since only the last one may expand into several entries.
+    pub nested: Vec<bool>,
+    /// Field-level options in declaration order.
+    pub field_attrs: Vec<FieldAttrs>,
+}
+
+impl FieldSpec<'_> {
+    /// Types of the fields whose values are encoded on the wire through their own `Encode`/
+    /// `Decode` impls, for trait bounds.
+    pub fn encoded_types(&self) -> Vec<&Type> {
+        self.order
+            .iter()
+            .filter(|&&idx| {
+                let attrs = &self.field_attrs[idx];
+                attrs.pad.is_none() && attrs.ascii_len.is_none()
+            })
+            .map(|&idx| self.field_types[idx])
+            .collect()
+    }
+
+    /// Type whose `Encode`/`Decode` impl handles the field's wire representation.
+    pub fn codec_type(&self, idx: usize) -> proc_macro2::TokenStream {
+        let attrs = &self.field_attrs[idx];
+        match attrs
+            .bytes_len
+            .as_ref()
+            .or(attrs.pad.as_ref())
+            .or(attrs.ascii_len.as_ref())
+        {
+            Some(len) => quote!([u8; #len]),
+            None => {
+                let ty = self.field_types[idx];
+                quote!(#ty)
+            }
+        }
+    }
+}
+
+/// Field-level options parsed from `#[pufu(...)]` attributes.
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// Store a byte container as exactly `N` bytes in the fixed region (`#[pufu(bytes_len = N)]`).
+    pub bytes_len: Option<syn::Expr>,
+    /// Require exactly `N` elements in a variable-length field (`#[pufu(assert_len = N)]`).
+    pub assert_len: Option<syn::Expr>,
+    /// Leave the field off the wire and fill it on decode (`#[pufu(skip)]`).
+    pub skip: bool,
+    /// Value for a skipped field on decode instead of `Default::default()`
+    /// (`#[pufu(default = "expr")]`).
+    pub default: Option<syn::Expr>,
+    /// Write `N` zero bytes into the fixed region in place of the field's value, and require
+    /// them to be zero on decode (`#[pufu(pad = N)]`). The field is filled with
+    /// `Default::default()` on decode.
+    pub pad: Option<syn::Expr>,
+    /// Store a `String`/`&str` as exactly `N` space-padded ASCII bytes in the fixed region, and
+    /// decode it as a `&str` with trailing spaces and NULs trimmed (`#[pufu(ascii_len = N)]`).
+    pub ascii_len: Option<syn::Expr>,
+    /// On decode, require as many elements as the earlier field `name` holds
+    /// (`#[pufu(count_from = "name")]`).
+    pub count_from: Option<syn::Ident>,
+}
+
+/// Parse field-level `#[pufu(...)]` attributes.
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pufu"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bytes_len") {
+                attrs.bytes_len = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("assert_len") {
+                attrs.assert_len = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("count_from") {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                attrs.count_from = Some(lit.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("ascii_len") {
+                attrs.ascii_len = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("pad") {
+                attrs.pad = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                attrs.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                let expr: syn::Expr = meta.value()?.parse()?;
+                attrs.default = Some(match expr {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }) => lit.parse()?,
+                    expr => expr,
+                });
+                Ok(())
+            } else {
+                Err(meta.error("unsupported pufu field attribute"))
+            }
+        })?;
+    }
+    if attrs.default.is_some() && !attrs.skip {
+        return Err(syn::Error::new(
+            field.span(),
+            "`default` is only supported on `#[pufu(skip)]` fields",
+        ));
+    }
+    if attrs.pad.is_some()
+        && (attrs.skip || attrs.bytes_len.is_some() || attrs.assert_len.is_some())
+    {
+        return Err(syn::Error::new(
+            field.span(),
+            "`pad` cannot be combined with `skip`, `bytes_len`, or `assert_len`",
+        ));
+    }
+    if attrs.count_from.is_some()
+        && (attrs.skip
+            || attrs.bytes_len.is_some()
+            || attrs.pad.is_some()
+            || attrs.ascii_len.is_some())
+    {
+        return Err(syn::Error::new(
+            field.span(),
+            "`count_from` cannot be combined with `skip`, `bytes_len`, `pad`, or `ascii_len`",
+        ));
+    }
+    if attrs.ascii_len.is_some()
+        && (attrs.skip
+            || attrs.bytes_len.is_some()
+            || attrs.assert_len.is_some()
+            || attrs.pad.is_some())
+    {
+        return Err(syn::Error::new(
+            field.span(),
+            "`ascii_len` cannot be combined with `skip`, `bytes_len`, `assert_len`, or `pad`",
+        ));
+    }
+    Ok(attrs)
+}
+
+/// Struct-level options parsed from `#[pufu(...)]` attributes.
+#[derive(Default)]
+pub struct StructAttrs {
+    /// Derive `PartialEq`, `Eq`, and `Hash` on the generated view (`#[pufu(hash)]`).
+    pub hash: bool,
+    /// Also derive `DataType` and `FixedDecode`, and encode the struct inline in the fixed
+    /// region so it can be a bare field, array element, or vector element (`#[pufu(fixed)]`).
+    pub fixed: bool,
+    /// Function called as `f(&view) -> Result<(), CodecError>` once the whole struct is decoded
+    /// (`#[pufu(validate = "path")]`).
+    pub validate: Option<syn::Path>,
+}
+
+/// Parse struct-level `#[pufu(...)]` attributes.
+pub fn parse_struct_attrs(input: &DeriveInput) -> syn::Result<StructAttrs> {
+    let mut attrs = StructAttrs::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pufu"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("hash") {
+                attrs.hash = true;
+                Ok(())
+            } else if meta.path.is_ident("fixed") {
+                attrs.fixed = true;
+                Ok(())
+            } else if meta.path.is_ident("validate") {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                attrs.validate = Some(lit.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported pufu attribute"))
+            }
+        })?;
+    }
+    Ok(attrs)
 }
 
 /// Collect named fields and compute var-field layout flags.
-pub fn collect_fields<'a>(input: &'a DeriveInput, label: &str) -> syn::Result<FieldSpec<'a>> {
+pub fn collect_fields<'a>(
+    input: &'a DeriveInput,
+    attrs: &StructAttrs,
+    label: &str,
+) -> syn::Result<FieldSpec<'a>> {
     let fields = match &input.data {
         syn::Data::Struct(data) => match &data.fields {
             syn::Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
@@ -45,6 +234,7 @@ pub fn collect_fields<'a>(input: &'a DeriveInput, label: &str) -> syn::Result<Fi
     let mut field_idents = Vec::with_capacity(fields.len());
     let mut field_types = Vec::with_capacity(fields.len());
     let mut field_vis = Vec::with_capacity(fields.len());
+    let mut field_attrs = Vec::with_capacity(fields.len());
 
     for field in &fields {
         let ident = field.ident.as_ref().ok_or_else(|| {
@@ -56,13 +246,69 @@ pub fn collect_fields<'a>(input: &'a DeriveInput, label: &str) -> syn::Result<Fi
         field_idents.push(ident);
         field_types.push(&field.ty);
         field_vis.push(&field.vis);
+        field_attrs.push(parse_field_attrs(field)?);
+    }
+
+    let kinds = field_types
+        .iter()
+        .zip(field_attrs.iter())
+        .map(|(ty, attrs)| {
+            if attrs.bytes_len.is_some() || attrs.pad.is_some() || attrs.ascii_len.is_some() {
+                VarKind::None
+            } else {
+                field_var_kind(ty)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if attrs.fixed {
+        for (idx, field_attrs) in field_attrs.iter().enumerate() {
+            if field_attrs.skip {
+                continue;
+            }
+            if kinds[idx] != VarKind::None
+                || field_attrs.bytes_len.is_some()
+                || field_attrs.assert_len.is_some()
+                || field_attrs.ascii_len.is_some()
+            {
+                return Err(syn::Error::new(
+                    field_types[idx].span(),
+                    "#[pufu(fixed)] requires every encoded field to be fixed-width",
+                ));
+            }
+        }
+    }
+
+    // Skipped fields never reach the wire. The rest keep declaration order; fixed and variable
+    // fields land in separate regions anyway, so interleaving them does not change the layout.
+    let order = (0..kinds.len())
+        .filter(|&idx| !field_attrs[idx].skip)
+        .collect::<Vec<_>>();
+
+    // `count_from` reads the count field's decoded value, so it must be decoded first.
+    for (pos, &idx) in order.iter().enumerate() {
+        let Some(count) = &field_attrs[idx].count_from else {
+            continue;
+        };
+        let decoded_before = order[..pos]
+            .iter()
+            .any(|&earlier| field_idents[earlier] == count);
+        if !decoded_before {
+            return Err(syn::Error::new(
+                count.span(),
+                format!(
+                    "`count_from` must name a field encoded before `{}`",
+                    field_idents[idx]
+                ),
+            ));
+        }
     }
 
     let mut var_field_indices = Vec::new();
     let mut var2_indices = Vec::new();
 
-    for (idx, ty) in field_types.iter().enumerate() {
-        match field_var_kind(ty) {
+    for &idx in &order {
+        match kinds[idx] {
             VarKind::Var1 => var_field_indices.push(idx),
             VarKind::Var2 => {
                 var_field_indices.push(idx);
@@ -73,23 +319,15 @@ pub fn collect_fields<'a>(input: &'a DeriveInput, label: &str) -> syn::Result<Fi
     }
 
     let last_var_index = if var_field_indices.is_empty() {
-        if field_types.is_empty() {
-            None
-        } else {
-            Some(field_types.len() - 1)
-        }
+        order.last().copied()
     } else {
         Some(*var_field_indices.last().expect("non-empty"))
     };
 
-    if let Some(last_var_index) = last_var_index {
-        for idx in var2_indices {
-            if idx != last_var_index {
-                return Err(syn::Error::new(
-                    field_types[idx].span(),
-                    "var2 data must be encoded/decoded as the last variable field",
-                ));
-            }
+    let mut nested = vec![false; field_types.len()];
+    for idx in var2_indices {
+        if Some(idx) != last_var_index {
+            nested[idx] = true;
         }
     }
 
@@ -110,6 +348,9 @@ pub fn collect_fields<'a>(input: &'a DeriveInput, label: &str) -> syn::Result<Fi
         field_types,
         field_vis,
         field_flags,
+        order,
+        nested,
+        field_attrs,
     })
 }
 
@@ -123,6 +364,33 @@ enum VarKind {
 
 /// Classify a field type as fixed, var1, or var2.
 fn field_var_kind(ty: &Type) -> VarKind {
+    if is_boxed_unsized(ty) || is_vec_deque(ty) {
+        return VarKind::Var1;
+    }
+
+    // Tuples encode their elements inline, so they carry the widest kind among them.
+    if let Type::Tuple(tuple) = ty {
+        return tuple
+            .elems
+            .iter()
+            .map(field_var_kind)
+            .fold(VarKind::None, |acc, kind| match (acc, kind) {
+                (VarKind::Var2, _) | (_, VarKind::Var2) => VarKind::Var2,
+                (VarKind::Var1, _) | (_, VarKind::Var1) => VarKind::Var1,
+                _ => VarKind::None,
+            });
+    }
+
+    // `None` writes only a presence byte, `Some` adds whatever the inner value encodes.
+    if let Some(inner) = generic_inner_type(ty, "Option") {
+        return field_var_kind(inner);
+    }
+
+    // `Rows<T>` writes one var entry per row, like `Vec<Vec<T>>`.
+    if generic_inner_type(ty, "Rows").is_some() {
+        return VarKind::Var2;
+    }
+
     let inner = match vec_inner_type(ty) {
         Some(inner) => inner,
         None => return VarKind::None,
@@ -135,8 +403,60 @@ fn field_var_kind(ty: &Type) -> VarKind {
     }
 }
 
+/// Detect `Box<[T]>`, `Box<str>`, `Box<CStr>`, `Cow<[T]>`, `Cow<str>`, and `CString`, which
+/// encode as var1 segments.
+fn is_boxed_unsized(ty: &Type) -> bool {
+    let segment = match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            match type_path.path.segments.last() {
+                Some(segment) if segment.ident == "CString" => return true,
+                Some(segment) if segment.ident == "Box" || segment.ident == "Cow" => segment,
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args,
+        _ => return false,
+    };
+
+    // `Cow` also carries a lifetime argument; only the pointee type matters.
+    let mut types = args.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    match (types.next(), types.next()) {
+        (Some(Type::Slice(_)), None) => true,
+        (Some(Type::Path(inner)), None) => inner
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "str" || segment.ident == "CStr"),
+        _ => false,
+    }
+}
+
+/// Detect `VecDeque<T>`, which encodes as a single var1 segment like `Vec<T>`.
+fn is_vec_deque(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "VecDeque"),
+        _ => false,
+    }
+}
+
 /// Extract the `T` in `Vec<T>` when applicable.
 fn vec_inner_type(ty: &Type) -> Option<&Type> {
+    generic_inner_type(ty, "Vec")
+}
+
+/// Return `T` for a path type `Name<T>` whose last segment is `name`.
+fn generic_inner_type<'t>(ty: &'t Type, name: &str) -> Option<&'t Type> {
     let type_path = match ty {
         Type::Path(type_path) => type_path,
         _ => return None,
@@ -147,7 +467,7 @@ fn vec_inner_type(ty: &Type) -> Option<&Type> {
     }
 
     let segment = type_path.path.segments.last()?;
-    if segment.ident != "Vec" {
+    if segment.ident != name {
         return None;
     }
 
@@ -3,17 +3,22 @@
 use quote::{format_ident, quote};
 use syn::DeriveInput;
 
-use crate::common::{add_trait_bounds, add_view_lifetime, collect_fields};
+use crate::common::{add_trait_bounds, add_view_lifetime, collect_fields, parse_struct_attrs};
 
 /// Expand a `#[derive(Decode)]` into the corresponding implementation.
 pub fn expand_decode(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    if let syn::Data::Enum(data) = &input.data {
+        return crate::repr_enum::expand_decode(input, data);
+    }
+
     let name = &input.ident;
     let view_ident = format_ident!("{}View", name);
-    let fields = collect_fields(input, "Decode")?;
+    let attrs = parse_struct_attrs(input)?;
+    let fields = collect_fields(input, &attrs, "Decode")?;
 
     let decode_generics = add_trait_bounds(
         &input.generics,
-        &fields.field_types,
+        &fields.encoded_types(),
         quote!(::pufu_core::Decode),
     );
     let view_generics = add_view_lifetime(&decode_generics)?;
@@ -22,23 +27,101 @@ pub fn expand_decode(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStrea
         decode_generics.split_for_impl();
     let (view_impl_generics, view_ty_generics, view_where_clause) = view_generics.split_for_impl();
 
-    let decode_fields = fields
-        .field_idents
-        .iter()
-        .zip(fields.field_types.iter())
-        .zip(fields.field_flags.iter())
-        .map(|((ident, ty), flag)| {
+    let decode_fields = fields.order.iter().map(|&idx| {
+        let ident = fields.field_idents[idx];
+        let ty = fields.codec_type(idx);
+        let flag = &fields.field_flags[idx];
+        if fields.field_attrs[idx].pad.is_some() {
+            return quote! {
+                let pad = <#ty as ::pufu_core::Decode>::decode_field::<false>(&mut nested_decoder)?;
+                if pad.iter().any(|&byte| byte != 0) {
+                    return Err(::pufu_core::CodecError::ValidationFailed);
+                }
+                let #ident = ::core::default::Default::default();
+            };
+        }
+        if fields.field_attrs[idx].ascii_len.is_some() {
+            return quote! {
+                let raw = <#ty as ::pufu_core::Decode>::decode_field::<false>(&mut nested_decoder)?;
+                let end = raw
+                    .iter()
+                    .rposition(|&byte| byte != b' ' && byte != 0)
+                    .map_or(0, |last| last + 1);
+                if !raw[..end].is_ascii() {
+                    return Err(::pufu_core::CodecError::ValidationFailed);
+                }
+                let #ident = ::core::str::from_utf8(&raw[..end])
+                    .map_err(|_| ::pufu_core::CodecError::InvalidUtf8)?;
+            };
+        }
+        let assert_len = fields.field_attrs[idx].assert_len.as_ref().map(|len| {
             quote! {
-                let #ident = <#ty as ::pufu_core::Decode>::decode_field::<#flag>(&mut nested_decoder)?;
+                if #ident.len() != #len {
+                    return Err(::pufu_core::CodecError::ValidationFailed);
+                }
             }
         });
+        let count_from = fields.field_attrs[idx].count_from.as_ref().map(|count| {
+            quote! {
+                let expected = <usize as ::core::convert::TryFrom<_>>::try_from(#count).ok();
+                if expected != Some(#ident.len()) {
+                    return Err(::pufu_core::CodecError::ValidationFailed);
+                }
+            }
+        });
+        if fields.nested[idx] {
+            return quote! {
+                let field_payload =
+                    <Vec<u8> as ::pufu_core::Decode>::decode_field::<false>(&mut nested_decoder)?;
+                let mut field_decoder =
+                    ::pufu_core::Decoder::new(field_payload, nested_decoder.config().clone())?;
+                let #ident = <#ty as ::pufu_core::Decode>::decode_field::<true>(&mut field_decoder)?;
+                #assert_len
+                #count_from
+            };
+        }
+        quote! {
+            let #ident = <#ty as ::pufu_core::Decode>::decode_field::<#flag>(&mut nested_decoder)?;
+            #assert_len
+            #count_from
+        }
+    });
+
+    let skipped_fields = fields
+        .field_attrs
+        .iter()
+        .enumerate()
+        .filter(|(_, attrs)| attrs.skip)
+        .map(|(idx, attrs)| {
+            let ident = fields.field_idents[idx];
+            let value = match &attrs.default {
+                Some(expr) => quote!(#expr),
+                None => quote!(::core::default::Default::default()),
+            };
+            quote! {
+                let #ident = #value;
+            }
+        })
+        .collect::<Vec<_>>();
 
     let view_fields = fields
         .field_idents
         .iter()
-        .zip(fields.field_types.iter())
         .zip(fields.field_vis.iter())
-        .map(|((ident, ty), vis)| {
+        .enumerate()
+        .map(|(idx, (ident, vis))| {
+            if fields.field_attrs[idx].skip || fields.field_attrs[idx].pad.is_some() {
+                let ty = fields.field_types[idx];
+                return quote! {
+                    #vis #ident: #ty,
+                };
+            }
+            if fields.field_attrs[idx].ascii_len.is_some() {
+                return quote! {
+                    #vis #ident: &'a str,
+                };
+            }
+            let ty = fields.codec_type(idx);
             quote! {
                 #vis #ident: <#ty as ::pufu_core::Decode>::View<'a>,
             }
@@ -46,25 +129,143 @@ pub fn expand_decode(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStrea
 
     let field_idents = &fields.field_idents;
 
-    let expanded = quote! {
-        pub struct #view_ident #view_impl_generics #view_where_clause {
-            #(#view_fields)*
+    // Without encoded fields nothing in the view borrows from the payload, so `'a` needs a marker
+    // and the nested payload is only validated, never read.
+    let (lifetime_field, lifetime_init, nested_decode) = if fields.order.is_empty() {
+        (
+            quote! {
+                #[doc(hidden)]
+                pub _lifetime: ::core::marker::PhantomData<&'a ()>,
+            },
+            quote!(_lifetime: ::core::marker::PhantomData,),
+            quote! {
+                ::pufu_core::Decoder::new(nested_payload, decoder.config().clone())?;
+            },
+        )
+    } else {
+        (
+            quote!(),
+            quote!(),
+            quote! {
+                let mut nested_decoder = ::pufu_core::Decoder::new(nested_payload, decoder.config().clone())?;
+            },
+        )
+    };
+
+    let validate = attrs.validate.as_ref().map(|path| {
+        quote! {
+            #path(&value)?;
         }
+    });
 
-        impl #decode_impl_generics ::pufu_core::Decode for #name #decode_ty_generics #decode_where_clause {
-            type View<'a> = #view_ident #view_ty_generics;
+    let view_derives = if attrs.hash {
+        quote!(#[derive(PartialEq, Eq, Hash)])
+    } else {
+        quote!()
+    };
 
-            fn decode_field<'a, const IS_LAST_VAR: bool>(
-                decoder: &mut ::pufu_core::Decoder<'a>,
-            ) -> ::core::result::Result<Self::View<'a>, ::pufu_core::CodecError> {
-                let nested_payload = <Vec<u8> as ::pufu_core::Decode>::decode_field::<IS_LAST_VAR>(
-                    decoder,
-                )?;
-                let mut nested_decoder = ::pufu_core::Decoder::new(nested_payload, decoder.config().clone())?;
-                #(#decode_fields)*
-                Ok(#view_ident {
-                    #(#field_idents),*
-                })
+    let fixed_impl = if attrs.fixed {
+        let fixed_generics = add_trait_bounds(
+            &input.generics,
+            &fields.encoded_types(),
+            quote!(::pufu_core::FixedDecode),
+        );
+        let (impl_generics, ty_generics, where_clause) = fixed_generics.split_for_impl();
+        let types = fields
+            .order
+            .iter()
+            .map(|&idx| fields.codec_type(idx))
+            .collect::<Vec<_>>();
+        let decode_fixed_fields = fields.order.iter().map(|&idx| {
+            let ident = fields.field_idents[idx];
+            let ty = fields.codec_type(idx);
+            if fields.field_attrs[idx].pad.is_some() {
+                return quote! {
+                    let (pad, bytes) = bytes.split_at(<#ty as ::pufu_core::FixedDecode>::LENGTH);
+                    if pad.iter().any(|&byte| byte != 0) {
+                        return Err(::pufu_core::CodecError::ValidationFailed);
+                    }
+                    let #ident = ::core::default::Default::default();
+                };
+            }
+            quote! {
+                let (field_bytes, bytes) =
+                    bytes.split_at(<#ty as ::pufu_core::FixedDecode>::LENGTH);
+                let #ident = <#ty as ::pufu_core::FixedDecode>::decode(field_bytes, endian)?;
+            }
+        });
+        quote! {
+            impl #impl_generics ::pufu_core::FixedDecode for #name #ty_generics #where_clause {
+                const LENGTH: usize = 0 #(+ <#types as ::pufu_core::FixedDecode>::LENGTH)*;
+
+                fn decode(
+                    bytes: &[u8],
+                    endian: ::pufu_core::Endian,
+                ) -> ::core::result::Result<Self, ::pufu_core::CodecError> {
+                    if bytes.len() != <Self as ::pufu_core::FixedDecode>::LENGTH {
+                        return Err(::pufu_core::CodecError::InvalidLength);
+                    }
+                    #(#decode_fixed_fields)*
+                    let _ = bytes;
+                    #(#skipped_fields)*
+                    let value = Self {
+                        #(#field_idents,)*
+                    };
+                    #validate
+                    Ok(value)
+                }
+            }
+
+            impl #impl_generics ::pufu_core::NotU8 for #name #ty_generics #where_clause {}
+        }
+    } else {
+        quote!()
+    };
+
+    // Fixed structs decode by value straight from the fixed region, so they are their own view.
+    let expanded = if attrs.fixed {
+        quote! {
+            #fixed_impl
+
+            impl #decode_impl_generics ::pufu_core::Decode for #name #decode_ty_generics #decode_where_clause {
+                type View<'a> = Self;
+
+                fn decode_field<'a, const IS_LAST_VAR: bool>(
+                    decoder: &mut ::pufu_core::Decoder<'a>,
+                ) -> ::core::result::Result<Self::View<'a>, ::pufu_core::CodecError> {
+                    let bytes = decoder
+                        .next_fixed_bytes(<Self as ::pufu_core::FixedDecode>::LENGTH as u32)?;
+                    <Self as ::pufu_core::FixedDecode>::decode(bytes, decoder.config().endian)
+                }
+            }
+        }
+    } else {
+        quote! {
+            #view_derives
+            pub struct #view_ident #view_impl_generics #view_where_clause {
+                #(#view_fields)*
+                #lifetime_field
+            }
+
+            impl #decode_impl_generics ::pufu_core::Decode for #name #decode_ty_generics #decode_where_clause {
+                type View<'a> = #view_ident #view_ty_generics;
+
+                fn decode_field<'a, const IS_LAST_VAR: bool>(
+                    decoder: &mut ::pufu_core::Decoder<'a>,
+                ) -> ::core::result::Result<Self::View<'a>, ::pufu_core::CodecError> {
+                    let nested_payload = <Vec<u8> as ::pufu_core::Decode>::decode_field::<IS_LAST_VAR>(
+                        decoder,
+                    )?;
+                    #nested_decode
+                    #(#decode_fields)*
+                    #(#skipped_fields)*
+                    let value = #view_ident {
+                        #(#field_idents,)*
+                        #lifetime_init
+                    };
+                    #validate
+                    Ok(value)
+                }
             }
         }
     };
@@ -3,46 +3,269 @@
 use quote::quote;
 use syn::DeriveInput;
 
-use crate::common::{add_trait_bounds, collect_fields};
+use crate::common::{add_trait_bounds, collect_fields, parse_struct_attrs, FieldSpec};
 
 /// Expand a `#[derive(Encode)]` into the corresponding implementation.
 pub fn expand_encode(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    if let syn::Data::Enum(data) = &input.data {
+        return crate::repr_enum::expand_encode(input, data);
+    }
+
     let name = &input.ident;
-    let fields = collect_fields(input, "Encode")?;
+    let attrs = parse_struct_attrs(input)?;
+    let fields = collect_fields(input, &attrs, "Encode")?;
 
     let encode_generics = add_trait_bounds(
         &input.generics,
-        &fields.field_types,
+        &fields.encoded_types(),
         quote!(::pufu_core::Encode),
     );
     let (encode_impl_generics, encode_ty_generics, encode_where_clause) =
         encode_generics.split_for_impl();
 
-    let encode_fields = fields
-        .field_idents
-        .iter()
-        .zip(fields.field_flags.iter())
-        .map(|(ident, flag)| {
+    let encode_fields = fields.order.iter().map(|&idx| {
+        let ident = fields.field_idents[idx];
+        let flag = &fields.field_flags[idx];
+        if let Some(len) = &fields.field_attrs[idx].pad {
+            return quote! {
+                <[u8; #len] as ::pufu_core::Encode>::encode_field::<false>(
+                    &[0u8; #len],
+                    &mut nested_encoder,
+                );
+            };
+        }
+        if let Some(len) = &fields.field_attrs[idx].ascii_len {
+            return quote! {
+                {
+                    let text: &str = ::core::convert::AsRef::<str>::as_ref(&self.#ident);
+                    assert!(
+                        text.is_ascii() && text.len() <= #len,
+                        concat!(
+                            "field `",
+                            stringify!(#ident),
+                            "` must be at most ",
+                            stringify!(#len),
+                            " ASCII bytes"
+                        )
+                    );
+                    let mut bytes = [b' '; #len];
+                    bytes[..text.len()].copy_from_slice(text.as_bytes());
+                    <[u8; #len] as ::pufu_core::Encode>::encode_field::<false>(
+                        &bytes,
+                        &mut nested_encoder,
+                    );
+                }
+            };
+        }
+        match &fields.field_attrs[idx].bytes_len {
+            Some(len) => quote! {
+                {
+                    let bytes: &[u8; #len] = ::core::convert::TryInto::try_into(
+                        ::core::convert::AsRef::<[u8]>::as_ref(&self.#ident),
+                    )
+                    .expect(concat!(
+                        "field `",
+                        stringify!(#ident),
+                        "` must be exactly ",
+                        stringify!(#len),
+                        " bytes"
+                    ));
+                    <&[u8; #len] as ::pufu_core::Encode>::encode_field::<#flag>(
+                        &bytes,
+                        &mut nested_encoder,
+                    );
+                }
+            },
+            None => {
+                let assert_len = fields.field_attrs[idx].assert_len.as_ref().map(|len| {
+                    quote! {
+                        assert!(
+                            self.#ident.len() == #len,
+                            concat!(
+                                "field `",
+                                stringify!(#ident),
+                                "` must have exactly ",
+                                stringify!(#len),
+                                " elements"
+                            )
+                        );
+                    }
+                });
+                if fields.nested[idx] {
+                    return quote! {
+                        #assert_len
+                        {
+                            let mut field_encoder =
+                                ::pufu_core::Encoder::new(nested_encoder.config().clone());
+                            self.#ident.encode_field::<true>(&mut field_encoder);
+                            let mut field_payload = Vec::new();
+                            field_encoder.finalize(&mut field_payload).expect("finalize");
+                            <Vec<u8> as ::pufu_core::Encode>::encode_field::<false>(
+                                &field_payload,
+                                &mut nested_encoder,
+                            );
+                        }
+                    };
+                }
+                quote! {
+                    #assert_len
+                    self.#ident.encode_field::<#flag>(&mut nested_encoder);
+                }
+            }
+        }
+    });
+
+    let measure_fields = fields.order.iter().map(|&idx| {
+        let ident = fields.field_idents[idx];
+        let flag = &fields.field_flags[idx];
+        let field_attrs = &fields.field_attrs[idx];
+        if let Some(len) = field_attrs
+            .pad
+            .as_ref()
+            .or(field_attrs.ascii_len.as_ref())
+            .or(field_attrs.bytes_len.as_ref())
+        {
+            return quote! {
+                nested_counter.add_fixed(#len);
+            };
+        }
+        let assert_len = field_attrs.assert_len.as_ref().map(|len| {
             quote! {
-                self.#ident.encode_field::<#flag>(&mut nested_encoder);
+                assert!(
+                    self.#ident.len() == #len,
+                    concat!(
+                        "field `",
+                        stringify!(#ident),
+                        "` must have exactly ",
+                        stringify!(#len),
+                        " elements"
+                    )
+                );
             }
         });
+        if fields.nested[idx] {
+            return quote! {
+                #assert_len
+                {
+                    let mut field_counter =
+                        ::pufu_core::CountingEncoder::new(nested_counter.config().clone());
+                    self.#ident.measure_field::<true>(&mut field_counter);
+                    nested_counter.add_nested(&field_counter);
+                }
+            };
+        }
+        quote! {
+            #assert_len
+            self.#ident.measure_field::<#flag>(&mut nested_counter);
+        }
+    });
+
+    let nested_encoder_binding = if fields.order.is_empty() {
+        quote!(nested_encoder)
+    } else {
+        quote!(mut nested_encoder)
+    };
+
+    let fixed_impl = if attrs.fixed {
+        let data_type_generics = add_trait_bounds(
+            &input.generics,
+            &fields.encoded_types(),
+            quote!(::pufu_core::DataType),
+        );
+        let (impl_generics, ty_generics, where_clause) = data_type_generics.split_for_impl();
+        let types = fields.order.iter().map(|&idx| fields.codec_type(idx));
+        let values = fields.order.iter().map(|&idx| fixed_value(&fields, idx));
+        quote! {
+            impl #impl_generics ::pufu_core::DataType for #name #ty_generics #where_clause {
+                const MODE: ::pufu_core::DataMode = ::pufu_core::DataMode::Fixed;
+                const LENGTH: usize = 0 #(+ <#types as ::pufu_core::DataType>::LENGTH)*;
+
+                fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &::pufu_core::Endian) {
+                    #(::pufu_core::DataType::push_fixed_data(#values, encoder_fixed, endian);)*
+                }
+
+                fn push_var1_data(
+                    &self,
+                    var_length: &mut Vec<u32>,
+                    data: &mut Vec<u8>,
+                    endian: &::pufu_core::Endian,
+                ) {
+                    ::pufu_core::DataType::push_fixed_data(self, data, endian);
+                    var_length.push(<Self as ::pufu_core::DataType>::LENGTH as u32);
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    // Fixed structs write themselves straight into the parent's fixed region through
+    // `DataType::push_fixed_data`, the same layout `FixedDecode` reads back, so bare fields and
+    // arrays of them need no var entry. Going through each field's `Encode` instead would differ
+    // for `Option` fields, whose `Encode` writes only a tag byte for `None`.
+    let encode_body = if attrs.fixed {
+        quote! {
+            encoder.push_fixed(self);
+        }
+    } else {
+        quote! {
+            let #nested_encoder_binding = ::pufu_core::Encoder::new(encoder.config().clone());
+            #(#encode_fields)*
+
+            let mut nested_payload = Vec::new();
+            nested_encoder.finalize(&mut nested_payload).expect("finalize");
+            <Vec<u8> as ::pufu_core::Encode>::encode_field::<IS_LAST_VAR>(
+                &nested_payload,
+                encoder,
+            );
+        }
+    };
+
+    let measure_body = if attrs.fixed {
+        quote! {
+            counter.add_fixed(<Self as ::pufu_core::DataType>::LENGTH);
+        }
+    } else {
+        let nested_counter_binding = if fields.order.is_empty() {
+            quote!(nested_counter)
+        } else {
+            quote!(mut nested_counter)
+        };
+        quote! {
+            let #nested_counter_binding =
+                ::pufu_core::CountingEncoder::new(counter.config().clone());
+            #(#measure_fields)*
+            counter.add_nested(&nested_counter);
+        }
+    };
 
     let expanded = quote! {
+        #fixed_impl
+
         impl #encode_impl_generics ::pufu_core::Encode for #name #encode_ty_generics #encode_where_clause {
             fn encode_field<const IS_LAST_VAR: bool>(&self, encoder: &mut ::pufu_core::Encoder) {
-                let mut nested_encoder = ::pufu_core::Encoder::new(encoder.config().clone());
-                #(#encode_fields)*
-
-                let mut nested_payload = Vec::new();
-                nested_encoder.finalize(&mut nested_payload).expect("finalize");
-                <Vec<u8> as ::pufu_core::Encode>::encode_field::<IS_LAST_VAR>(
-                    &nested_payload,
-                    encoder,
-                );
+                #encode_body
+            }
+
+            fn measure_field<const IS_LAST_VAR: bool>(
+                &self,
+                counter: &mut ::pufu_core::CountingEncoder,
+            ) {
+                #measure_body
             }
         }
     };
 
     Ok(expanded)
 }
+
+/// Expression borrowing what a fixed struct writes for field `idx`: zeros for `pad` fields.
+fn fixed_value(fields: &FieldSpec<'_>, idx: usize) -> proc_macro2::TokenStream {
+    match &fields.field_attrs[idx].pad {
+        Some(len) => quote!(&[0u8; #len]),
+        None => {
+            let ident = fields.field_idents[idx];
+            quote!(&self.#ident)
+        }
+    }
+}
@@ -6,9 +6,14 @@ use syn::{parse_macro_input, DeriveInput};
 mod common;
 mod decode;
 mod encode;
-
-#[proc_macro_derive(Encode)]
-/// Derive `pufu_core::Encode` for named-field structs.
+mod repr_enum;
+
+#[proc_macro_derive(Encode, attributes(pufu))]
+/// Derive `pufu_core::Encode` for named-field structs and fieldless `#[repr(u8)]` enums.
+///
+/// Enums are written as one fixed byte holding the variant's discriminant. With
+/// `#[pufu(via = u8)]` the byte comes from the enum's own `Clone` and `Into<u8>` impls instead,
+/// and any enum shape is accepted.
 pub fn derive_encode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -20,8 +25,15 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(Decode)]
-/// Derive `pufu_core::Decode` for named-field structs.
+#[proc_macro_derive(Decode, attributes(pufu))]
+/// Derive `pufu_core::Decode` for named-field structs and fieldless `#[repr(u8)]` enums.
+///
+/// The generated view re-parses the nested payload in place, so byte fields (`Vec<u8>`,
+/// `Box<[u8]>`, `[u8; N]`, ...) borrow from the top-level buffer at any nesting depth. Wider
+/// element vectors such as `Vec<u16>` are decoded into owned `Vec`s and do copy.
+///
+/// Enums decode by value; a byte that matches no discriminant (or that the `TryFrom<u8>` impl of a
+/// `#[pufu(via = u8)]` enum rejects) is `CodecError::ValidationFailed`.
 pub fn derive_decode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -0,0 +1,198 @@
+//! Encode/Decode expansion for fieldless `#[repr(u8)]` enums and `#[pufu(via = u8)]` enums.
+//!
+//! Such enums are written as a single fixed byte holding the discriminant (or the user's
+//! `Into<u8>` mapping), so they also get `DataType`, `FixedDecode`, and `NotU8` impls and can be
+//! array or vector elements.
+
+use quote::quote;
+use syn::{spanned::Spanned, DataEnum, DeriveInput};
+
+/// How an enum maps to its tag byte.
+enum TagMapping<'a> {
+    /// Fieldless `#[repr(u8)]` enum; the tag is the variant's discriminant.
+    Discriminant(Vec<&'a syn::Ident>),
+    /// `#[pufu(via = u8)]`; the tag comes from the enum's own `Into<u8>` and `TryFrom<u8>`.
+    Via,
+}
+
+impl TagMapping<'_> {
+    /// Expression converting `self` into its `u8` tag.
+    fn encode_tag(&self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Discriminant(variants) => quote! {
+                match self {
+                    #(Self::#variants => Self::#variants as u8,)*
+                }
+            },
+            Self::Via => quote! {
+                <Self as ::core::convert::Into<u8>>::into(::core::clone::Clone::clone(self))
+            },
+        }
+    }
+
+    /// Statements mapping the `u8` in `tag` back to `Self`.
+    fn decode_tag(&self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Discriminant(variants) => quote! {
+                #(
+                    if tag == Self::#variants as u8 {
+                        return Ok(Self::#variants);
+                    }
+                )*
+                Err(::pufu_core::CodecError::ValidationFailed)
+            },
+            Self::Via => quote! {
+                <Self as ::core::convert::TryFrom<u8>>::try_from(tag)
+                    .map_err(|_| ::pufu_core::CodecError::ValidationFailed)
+            },
+        }
+    }
+}
+
+/// Return `true` if the enum carries `#[pufu(via = u8)]`.
+fn parse_via(input: &DeriveInput) -> syn::Result<bool> {
+    let mut via = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pufu"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("via") {
+                let ty: syn::Type = meta.value()?.parse()?;
+                let is_u8 = matches!(&ty, syn::Type::Path(path) if path.path.is_ident("u8"));
+                if !is_u8 {
+                    return Err(syn::Error::new(ty.span(), "only `via = u8` is supported"));
+                }
+                via = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported pufu enum attribute"))
+            }
+        })?;
+    }
+    Ok(via)
+}
+
+/// Work out the tag mapping, rejecting enums that are neither `#[pufu(via = u8)]` nor fieldless
+/// `#[repr(u8)]`.
+fn tag_mapping<'a>(
+    input: &DeriveInput,
+    data: &'a DataEnum,
+    label: &str,
+) -> syn::Result<TagMapping<'a>> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            format!("{label} cannot be derived for generic enums"),
+        ));
+    }
+    if parse_via(input)? {
+        return Ok(TagMapping::Via);
+    }
+
+    let mut repr_u8 = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("u8") {
+                repr_u8 = true;
+            }
+            Ok(())
+        })?;
+    }
+    if !repr_u8 {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            format!(
+                "{label} can only be derived for enums with `#[repr(u8)]` or `#[pufu(via = u8)]`"
+            ),
+        ));
+    }
+
+    data.variants
+        .iter()
+        .map(|variant| match variant.fields {
+            syn::Fields::Unit => Ok(&variant.ident),
+            _ => Err(syn::Error::new(
+                variant.fields.span(),
+                format!("{label} can only be derived for fieldless enums"),
+            )),
+        })
+        .collect::<syn::Result<_>>()
+        .map(TagMapping::Discriminant)
+}
+
+/// Expand `#[derive(Encode)]` for a fieldless `#[repr(u8)]` or `#[pufu(via = u8)]` enum.
+pub fn expand_encode(
+    input: &DeriveInput,
+    data: &DataEnum,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let tag = tag_mapping(input, data, "Encode")?.encode_tag();
+
+    Ok(quote! {
+        impl ::pufu_core::DataType for #name {
+            const MODE: ::pufu_core::DataMode = ::pufu_core::DataMode::Fixed;
+            const LENGTH: usize = 1;
+
+            fn push_fixed_data(&self, encoder_fixed: &mut Vec<u8>, endian: &::pufu_core::Endian) {
+                let tag: u8 = #tag;
+                ::pufu_core::DataType::push_fixed_data(&tag, encoder_fixed, endian);
+            }
+        }
+
+        impl ::pufu_core::Encode for #name {
+            fn encode_field<const IS_LAST_VAR: bool>(&self, encoder: &mut ::pufu_core::Encoder) {
+                let tag: u8 = #tag;
+                ::pufu_core::Encode::encode_field::<false>(&tag, encoder);
+            }
+
+            fn measure_field<const IS_LAST_VAR: bool>(
+                &self,
+                counter: &mut ::pufu_core::CountingEncoder,
+            ) {
+                counter.add_fixed(1);
+            }
+        }
+    })
+}
+
+/// Expand `#[derive(Decode)]` for a fieldless `#[repr(u8)]` or `#[pufu(via = u8)]` enum.
+pub fn expand_decode(
+    input: &DeriveInput,
+    data: &DataEnum,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let from_tag = tag_mapping(input, data, "Decode")?.decode_tag();
+
+    Ok(quote! {
+        impl ::pufu_core::FixedDecode for #name {
+            const LENGTH: usize = 1;
+
+            fn decode(
+                bytes: &[u8],
+                endian: ::pufu_core::Endian,
+            ) -> ::core::result::Result<Self, ::pufu_core::CodecError> {
+                let tag = <u8 as ::pufu_core::FixedDecode>::decode(bytes, endian)?;
+                #from_tag
+            }
+        }
+
+        impl ::pufu_core::NotU8 for #name {}
+
+        impl ::pufu_core::Decode for #name {
+            type View<'a> = Self;
+
+            fn decode_field<'a, const IS_LAST_VAR: bool>(
+                decoder: &mut ::pufu_core::Decoder<'a>,
+            ) -> ::core::result::Result<Self::View<'a>, ::pufu_core::CodecError> {
+                let bytes = decoder.next_fixed_bytes(1)?;
+                <Self as ::pufu_core::FixedDecode>::decode(bytes, decoder.config().endian)
+            }
+        }
+    })
+}
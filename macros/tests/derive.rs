@@ -180,3 +180,1039 @@ fn derive_encode_decode_roundtrip_tuple_field() {
     assert_eq!(view.pair, value.pair);
     assert_eq!(view.tail, value.tail.as_slice());
 }
+
+#[derive(Encode, Decode)]
+#[pufu(hash)]
+/// Payload whose view derives `Hash` and `Eq`.
+struct HashablePayload {
+    id: u16,
+    name: Vec<u8>,
+}
+
+#[test]
+fn derive_decode_hash_view_dedups_in_hash_set() {
+    let values = [
+        HashablePayload {
+            id: 1,
+            name: b"alpha".to_vec(),
+        },
+        HashablePayload {
+            id: 1,
+            name: b"alpha".to_vec(),
+        },
+        HashablePayload {
+            id: 2,
+            name: b"beta".to_vec(),
+        },
+    ];
+
+    let encoded = values
+        .iter()
+        .map(|value| {
+            let mut encoder = Encoder::new(Config::default());
+            value.encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            out
+        })
+        .collect::<Vec<_>>();
+
+    let mut seen = std::collections::HashSet::new();
+    for buf in &encoded {
+        let mut decoder = Decoder::new(buf, Config::default()).expect("decoder");
+        seen.insert(HashablePayload::decode_field::<true>(&mut decoder).expect("view"));
+    }
+
+    assert_eq!(seen.len(), 2);
+}
+
+#[derive(Encode, Decode)]
+/// Payload storing a digest as exactly 32 bytes in the fixed region.
+struct DigestPayload {
+    id: u8,
+    #[pufu(bytes_len = 32)]
+    digest: Vec<u8>,
+    tail: Vec<u8>,
+}
+
+#[derive(Encode, Decode)]
+/// Payload with a digest that is too short for `DigestPayload`.
+struct ShortDigestPayload {
+    id: u8,
+    digest: [u8; 16],
+    tail: Vec<u8>,
+}
+
+#[test]
+fn derive_bytes_len_roundtrips_as_fixed_array_view() {
+    let value = DigestPayload {
+        id: 9,
+        digest: (0..32).collect(),
+        tail: vec![0xaa],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = DigestPayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.id, value.id);
+    let digest: &[u8; 32] = view.digest;
+    assert_eq!(digest.as_slice(), value.digest.as_slice());
+    assert_eq!(view.tail, value.tail.as_slice());
+}
+
+#[test]
+fn derive_bytes_len_rejects_short_digest() {
+    let value = ShortDigestPayload {
+        id: 9,
+        digest: [0x11; 16],
+        tail: vec![0xaa],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    assert!(matches!(
+        DigestPayload::decode_field::<true>(&mut decoder),
+        Err(pufu_core::CodecError::Truncated { .. })
+    ));
+}
+
+#[derive(Encode, Decode)]
+/// Quote with a currency code stored as four fixed ASCII bytes.
+struct QuotePayload {
+    #[pufu(ascii_len = 4)]
+    currency: String,
+    #[pufu(ascii_len = 4)]
+    venue: &'static str,
+    price: u32,
+}
+
+#[test]
+fn derive_ascii_len_pads_and_trims_fixed_text() {
+    let value = QuotePayload {
+        currency: "USD".to_string(),
+        venue: "",
+        price: 100,
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let nested = Decoder::new(decoder.peek_var().expect("nested"), Config::default())
+        .expect("nested decoder");
+    assert_eq!(nested.peek_fixed(8), Ok(&b"USD     "[..]));
+
+    let view = QuotePayload::decode_field::<true>(&mut decoder).expect("view");
+    let currency: &str = view.currency;
+    assert_eq!(currency, "USD");
+    assert_eq!(view.venue, "");
+    assert_eq!(view.price, 100);
+}
+
+#[test]
+#[should_panic(expected = "field `currency` must be at most 4 ASCII bytes")]
+fn derive_ascii_len_rejects_long_text() {
+    let value = QuotePayload {
+        currency: "EURO!".to_string(),
+        venue: "X",
+        price: 0,
+    };
+    value.encode_field::<true>(&mut Encoder::new(Config::default()));
+}
+
+#[derive(Encode, Decode)]
+#[pufu(validate = "check_framed")]
+/// Payload whose `len` field must match the length of `data`.
+struct FramedPayload {
+    len: u16,
+    data: Vec<u8>,
+}
+
+fn check_framed(view: &FramedPayloadView<'_>) -> Result<(), pufu_core::CodecError> {
+    if usize::from(view.len) == view.data.len() {
+        Ok(())
+    } else {
+        Err(pufu_core::CodecError::ValidationFailed)
+    }
+}
+
+#[test]
+fn derive_validate_hook_checks_decoded_view() {
+    for (value, expected) in [
+        (
+            FramedPayload {
+                len: 2,
+                data: vec![1, 2],
+            },
+            true,
+        ),
+        (
+            FramedPayload {
+                len: 3,
+                data: vec![1, 2],
+            },
+            false,
+        ),
+    ] {
+        let mut encoder = Encoder::new(Config::default());
+        value.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let result = FramedPayload::decode_field::<true>(&mut decoder);
+        if expected {
+            assert_eq!(result.expect("view").data, &[1, 2]);
+        } else {
+            assert!(matches!(
+                result,
+                Err(pufu_core::CodecError::ValidationFailed)
+            ));
+        }
+    }
+}
+
+#[derive(Encode, Decode)]
+/// Payload made only of variable-length fields, ending in a var2 field.
+struct AllVarPayload {
+    a: Vec<u8>,
+    b: Vec<u8>,
+    c: Vec<Vec<u16>>,
+}
+
+#[test]
+fn derive_encode_decode_roundtrip_all_var_fields() {
+    let value = AllVarPayload {
+        a: vec![0x01, 0x02],
+        b: vec![],
+        c: vec![vec![3, 4], vec![], vec![5]],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = AllVarPayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.a, value.a.as_slice());
+    assert_eq!(view.b, value.b.as_slice());
+    assert_eq!(view.c, value.c);
+}
+
+#[test]
+fn derive_encode_through_reference_matches_owned() {
+    let value = SimplePayload {
+        id: 0x0102,
+        payload: vec![0x03, 0x04],
+    };
+
+    let mut owned_encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut owned_encoder);
+    let mut owned = Vec::new();
+    owned_encoder.finalize(&mut owned).expect("finalize");
+
+    let value_ref: &SimplePayload = &value;
+    let mut ref_encoder = Encoder::new(Config::default());
+    <&SimplePayload as EncodeTrait>::encode_field::<true>(&value_ref, &mut ref_encoder);
+    let mut by_ref = Vec::new();
+    ref_encoder.finalize(&mut by_ref).expect("finalize");
+
+    assert_eq!(by_ref, owned);
+}
+
+/// Fill value for `VersionedPayload::label` when it is absent from the wire.
+fn default_label() -> String {
+    "unlabeled".to_string()
+}
+
+#[derive(Encode, Decode)]
+/// Payload with fields that are not encoded and are filled on decode.
+struct VersionedPayload {
+    id: u16,
+    #[pufu(skip, default = "default_label()")]
+    label: String,
+    #[pufu(skip)]
+    retries: u32,
+    data: Vec<u8>,
+}
+
+#[test]
+fn derive_skip_fields_decode_to_defaults() {
+    let value = VersionedPayload {
+        id: 0x0304,
+        label: "ignored".to_string(),
+        retries: 7,
+        data: vec![0x01],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let simple = SimplePayload {
+        id: value.id,
+        payload: value.data.clone(),
+    };
+    let mut simple_encoder = Encoder::new(Config::default());
+    simple.encode_field::<true>(&mut simple_encoder);
+    let mut simple_out = Vec::new();
+    simple_encoder.finalize(&mut simple_out).expect("finalize");
+    assert_eq!(out, simple_out);
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = VersionedPayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.label, "unlabeled");
+    assert_ne!(view.label, value.label);
+    assert_eq!(view.retries, 0);
+    assert_ne!(view.retries, value.retries);
+    assert_eq!(view.data, value.data.as_slice());
+}
+
+#[derive(Encode, Decode)]
+/// Payload with boxed immutable byte and string fields.
+struct BoxedPayload {
+    name: Box<str>,
+    id: u16,
+    data: Box<[u8]>,
+    label: std::borrow::Cow<'static, str>,
+    c_name: Box<std::ffi::CStr>,
+}
+
+#[test]
+fn derive_encode_decode_roundtrip_boxed_fields() {
+    let value = BoxedPayload {
+        name: "boxed".into(),
+        id: 0x0a0b,
+        data: vec![1, 2, 3].into_boxed_slice(),
+        label: "label".into(),
+        c_name: c"c_name".into(),
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = BoxedPayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.name, &*value.name);
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.data, &*value.data);
+    assert_eq!(view.label, &*value.label);
+    assert_eq!(view.c_name, &*value.c_name);
+}
+
+#[derive(Encode, Decode)]
+/// Payload with a ring-buffer field.
+struct QueuePayload {
+    queue: std::collections::VecDeque<u32>,
+    id: u8,
+}
+
+#[test]
+fn derive_encode_decode_roundtrip_vec_deque_field() {
+    let mut queue = std::collections::VecDeque::from(vec![1u32, 2, 3]);
+    queue.push_front(0);
+    let value = QueuePayload { queue, id: 9 };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = QueuePayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.queue, value.queue);
+    assert_eq!(view.id, value.id);
+}
+
+#[derive(Encode, Decode)]
+/// Payload whose trailing tuple field carries var and var2 elements.
+struct TuplePayload {
+    id: u16,
+    pair: (u8, Vec<u8>),
+    tail: (u32, Vec<Vec<u16>>),
+}
+
+#[test]
+fn derive_encode_decode_roundtrip_tuple_fields() {
+    let value = TuplePayload {
+        id: 3,
+        pair: (1, vec![2, 3]),
+        tail: (4, vec![vec![5], vec![6, 7]]),
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = TuplePayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.pair, (1, value.pair.1.as_slice()));
+    assert_eq!(view.tail, value.tail);
+}
+
+#[derive(Encode, Decode)]
+/// Payload whose last var field is optional bytes.
+struct OptionalBytesPayload {
+    id: u16,
+    name: Vec<u8>,
+    extra: Option<Vec<u8>>,
+    flags: u8,
+}
+
+#[test]
+fn derive_encode_decode_roundtrip_optional_bytes() {
+    for extra in [Some(vec![1, 2]), None] {
+        let value = OptionalBytesPayload {
+            id: 7,
+            name: b"opt".to_vec(),
+            extra,
+            flags: 0x80,
+        };
+
+        let mut encoder = Encoder::new(Config::default());
+        value.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let view = OptionalBytesPayload::decode_field::<true>(&mut decoder).expect("view");
+
+        assert_eq!(view.id, value.id);
+        assert_eq!(view.name, value.name.as_slice());
+        assert_eq!(view.extra, value.extra.as_deref());
+        assert_eq!(view.flags, value.flags);
+    }
+}
+
+#[test]
+fn derive_roundtrip_with_custom_config() {
+    let config = Config::builder().big().magic(*b"mine").version(2).build();
+    let value = SimplePayload {
+        id: 0x0102,
+        payload: vec![3, 4, 5],
+    };
+
+    let out = value.encode_to_vec_with(&config).expect("encode");
+    assert_eq!(&out[..5], b"mine\x02");
+
+    let view = SimplePayload::decode_with(&out, &config).expect("decode");
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.payload, value.payload.as_slice());
+
+    let little = Config::builder().magic(*b"mine").version(2).build();
+    assert!(SimplePayload::decode_with(&out, &little).is_err());
+    assert!(SimplePayload::decode_with(&out, &Config::default()).is_err());
+}
+
+#[derive(Encode, Decode)]
+/// Outer payload embedding a derived struct with borrowed bytes.
+struct EnvelopePayload {
+    version: u8,
+    inner: SimplePayload,
+    trailer: Vec<u8>,
+}
+
+#[test]
+fn derive_nested_view_borrows_from_top_level_buffer() {
+    let value = EnvelopePayload {
+        version: 1,
+        inner: SimplePayload {
+            id: 0x0102,
+            payload: vec![0xaa, 0xbb, 0xcc],
+        },
+        trailer: vec![0xdd],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = EnvelopePayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.inner.payload, value.inner.payload.as_slice());
+    let buffer = out.as_ptr_range();
+    let inner = view.inner.payload.as_ptr_range();
+    assert!(buffer.start <= inner.start && inner.end <= buffer.end);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[pufu(fixed)]
+/// Fixed-width point usable as a vector element.
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+#[derive(Encode, Decode)]
+/// Payload holding a var segment of fixed structs.
+struct PolylinePayload {
+    id: u8,
+    points: Vec<Point>,
+}
+
+#[test]
+fn derive_fixed_struct_roundtrips_as_vec_element() {
+    assert_eq!(<Point as pufu_core::FixedDecode>::LENGTH, 8);
+
+    let value = PolylinePayload {
+        id: 4,
+        points: vec![Point { x: 1, y: 2 }, Point { x: u32::MAX, y: 0 }],
+    };
+
+    let mut encoder = Encoder::new(Config::builder().big().build());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+    let view = PolylinePayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.points, value.points);
+}
+
+#[derive(Encode, Decode)]
+/// Payload with a fixed-size grid of fixed structs and a bare fixed struct field.
+struct GridPayload {
+    origin: Point,
+    grid: [Point; 4],
+    label: Vec<u8>,
+}
+
+#[test]
+fn derive_fixed_struct_array_lands_in_fixed_region() {
+    let value = GridPayload {
+        origin: Point { x: 7, y: 9 },
+        grid: [
+            Point { x: 1, y: 2 },
+            Point { x: 3, y: 4 },
+            Point { x: u32::MAX, y: 0 },
+            Point { x: 0, y: u32::MAX },
+        ],
+        label: vec![0xaa, 0xbb],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let nested = Decoder::new(decoder.peek_var().expect("nested"), Config::default())
+        .expect("nested decoder");
+    assert_eq!(nested.fixed_region_len(), 5 * 8);
+    assert_eq!(nested.var_count(), 1);
+
+    let view = GridPayload::decode_field::<true>(&mut decoder).expect("view");
+
+    assert_eq!(view.origin, value.origin);
+    assert_eq!(view.grid, value.grid);
+    assert_eq!(view.label, value.label.as_slice());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[pufu(fixed)]
+/// Fixed struct with an optional member, which always takes `1 + 4` bytes inline.
+struct MaybePoint {
+    a: Option<u32>,
+    b: u16,
+}
+
+#[test]
+fn derive_fixed_struct_with_option_roundtrips_as_bare_field() {
+    for value in [
+        MaybePoint { a: None, b: 7 },
+        MaybePoint {
+            a: Some(0x0102_0304),
+            b: 7,
+        },
+    ] {
+        let mut encoder = Encoder::new(Config::default());
+        value.encode_field::<false>(&mut encoder);
+        9u8.encode_field::<false>(&mut encoder);
+        assert_eq!(encoder.fixed_bytes().len(), 1 + 4 + 2 + 1);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        assert_eq!(MaybePoint::decode_field::<false>(&mut decoder), Ok(value));
+        assert_eq!(u8::decode_field::<false>(&mut decoder), Ok(9));
+    }
+}
+
+#[derive(Encode, Decode)]
+/// Payload whose var segment must hold exactly four bytes.
+struct ExactLenPayload {
+    id: u8,
+    #[pufu(assert_len = 4)]
+    key: Vec<u8>,
+}
+
+#[derive(Encode, Decode)]
+/// Same layout as `ExactLenPayload` without the length guard.
+struct AnyLenPayload {
+    id: u8,
+    key: Vec<u8>,
+}
+
+#[test]
+fn derive_assert_len_accepts_exact_length() {
+    let value = ExactLenPayload {
+        id: 1,
+        key: vec![1, 2, 3, 4],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = ExactLenPayload::decode_field::<true>(&mut decoder).expect("view");
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.key, value.key.as_slice());
+}
+
+#[test]
+fn derive_assert_len_rejects_wrong_length_on_decode() {
+    let value = AnyLenPayload {
+        id: 1,
+        key: vec![1, 2, 3],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    assert!(matches!(
+        ExactLenPayload::decode_field::<true>(&mut decoder),
+        Err(pufu_core::CodecError::ValidationFailed)
+    ));
+}
+
+#[test]
+#[should_panic(expected = "field `key` must have exactly 4 elements")]
+fn derive_assert_len_panics_on_wrong_length_encode() {
+    let value = ExactLenPayload {
+        id: 1,
+        key: vec![1, 2, 3, 4, 5],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+}
+
+/// Encode any value through a generic reference as a top-level payload.
+fn encode_any<T: EncodeTrait + ?Sized>(value: &T) -> Vec<u8> {
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+    out
+}
+
+#[test]
+fn generic_encode_through_references() {
+    let id: u32 = 0x0102_0304;
+    let encoded_id = encode_any(&id);
+    assert_eq!(encoded_id, encode_any(&&id));
+    let mut decoder = Decoder::new(&encoded_id, Config::default()).expect("decoder");
+    assert_eq!(u32::decode_field::<true>(&mut decoder), Ok(id));
+
+    let values: &[u16] = &[1, 2, 3];
+    assert_eq!(encode_any(values), encode_any(&values.to_vec()));
+
+    let value = SimplePayload {
+        id: 7,
+        payload: vec![8, 9],
+    };
+    let by_ref = encode_any(&&value);
+    assert_eq!(by_ref, encode_any(&value));
+
+    let mut decoder = Decoder::new(&by_ref, Config::default()).expect("decoder");
+    let view = SimplePayload::decode_field::<true>(&mut decoder).expect("view");
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.payload, value.payload.as_slice());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[repr(u8)]
+/// C-like enum stored as its discriminant byte.
+enum Kind {
+    A,
+    B = 5,
+    C,
+}
+
+#[derive(Encode, Decode)]
+/// Payload mixing a bare enum field with a vector of enums.
+struct KindPayload {
+    kind: Kind,
+    history: Vec<Kind>,
+}
+
+#[test]
+fn derive_repr_u8_enum_roundtrips_as_one_byte() {
+    let value = KindPayload {
+        kind: Kind::C,
+        history: vec![Kind::A, Kind::B, Kind::C],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let nested = Decoder::new(decoder.peek_var().expect("nested"), Config::default())
+        .expect("nested decoder");
+    assert_eq!(nested.fixed_region_len(), 1);
+    assert_eq!(nested.all_var_segments(), Ok(vec![&[0u8, 5, 6][..]]));
+
+    let view = KindPayload::decode_field::<true>(&mut decoder).expect("view");
+    assert_eq!(view.kind, Kind::C);
+    assert_eq!(view.history, value.history);
+}
+
+#[test]
+fn derive_repr_u8_enum_rejects_unknown_discriminant() {
+    let mut encoder = Encoder::new(Config::default());
+    1u8.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    assert_eq!(
+        Kind::decode_field::<true>(&mut decoder),
+        Err(pufu_core::CodecError::ValidationFailed)
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[pufu(via = u8)]
+/// Enum with a hand-written byte mapping that differs from its discriminants.
+enum Side {
+    Buy,
+    Sell,
+}
+
+impl From<Side> for u8 {
+    fn from(side: Side) -> u8 {
+        match side {
+            Side::Buy => b'B',
+            Side::Sell => b'S',
+        }
+    }
+}
+
+impl TryFrom<u8> for Side {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Self, u8> {
+        match byte {
+            b'B' => Ok(Side::Buy),
+            b'S' => Ok(Side::Sell),
+            other => Err(other),
+        }
+    }
+}
+
+#[test]
+fn derive_via_u8_enum_uses_user_conversions() {
+    let value = (Side::Sell, vec![Side::Buy, Side::Sell]);
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    assert_eq!(encoder.fixed_bytes(), b"S");
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    assert_eq!(decoder.all_var_segments(), Ok(vec![&b"BS"[..]]));
+    let view = <(Side, Vec<Side>)>::decode_field::<true>(&mut decoder).expect("view");
+    assert_eq!(view, value);
+
+    let mut encoder = Encoder::new(Config::default());
+    0u8.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    assert_eq!(
+        Side::decode_field::<true>(&mut decoder),
+        Err(pufu_core::CodecError::ValidationFailed)
+    );
+}
+
+#[derive(Encode, Decode)]
+/// Payload reserving four zero bytes between two fixed fields.
+struct PaddedPayload {
+    id: u8,
+    #[pufu(pad = 4)]
+    _reserved: (),
+    flags: u16,
+    name: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[pufu(fixed)]
+/// Fixed struct with trailing reserved bytes.
+struct PaddedPoint {
+    x: u16,
+    #[pufu(pad = 2)]
+    _reserved: (),
+}
+
+#[test]
+fn derive_pad_writes_and_consumes_zero_bytes() {
+    let value = PaddedPayload {
+        id: 1,
+        _reserved: (),
+        flags: 0x0203,
+        name: vec![0xee],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let nested_bytes = decoder.peek_var().expect("nested");
+    let mut nested = Decoder::new(nested_bytes, Config::default()).expect("nested decoder");
+    assert_eq!(
+        nested.next_fixed_bytes(7),
+        Ok(&[0x01, 0, 0, 0, 0, 0x03, 0x02][..])
+    );
+
+    let view = PaddedPayload::decode_field::<true>(&mut decoder).expect("view");
+    assert_eq!(view.id, 1);
+    assert_eq!(view.flags, 0x0203);
+    assert_eq!(view.name, &[0xee][..]);
+
+    // A non-zero reserved byte is rejected.
+    let mut tampered = nested_bytes.to_vec();
+    tampered[9] = 0xff;
+    let mut encoder = Encoder::new(Config::default());
+    tampered.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    assert_eq!(
+        PaddedPayload::decode_field::<true>(&mut decoder).err(),
+        Some(pufu_core::CodecError::ValidationFailed)
+    );
+
+    assert_eq!(<PaddedPoint as pufu_core::FixedDecode>::LENGTH, 4);
+    let points = vec![
+        PaddedPoint {
+            x: 5,
+            _reserved: ()
+        };
+        2
+    ];
+    let mut encoder = Encoder::new(Config::default());
+    points.encode_field::<true>(&mut encoder);
+    assert_eq!(encoder.data_bytes(), &[5, 0, 0, 0, 5, 0, 0, 0]);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    assert_eq!(
+        <Vec<PaddedPoint>>::decode_field::<true>(&mut decoder),
+        Ok(points)
+    );
+}
+
+#[test]
+fn derive_by_value_types_pass_roundtrip_harness() {
+    use pufu_core::test_util::assert_roundtrip;
+    use pufu_core::Endian;
+
+    for endian in [Endian::Little, Endian::Big] {
+        assert_roundtrip(&Point { x: 3, y: u32::MAX }, endian);
+        assert_roundtrip(&vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }], endian);
+        assert_roundtrip(&Kind::B, endian);
+        assert_roundtrip(&[Kind::C, Kind::A], endian);
+    }
+}
+
+#[derive(Encode, Decode)]
+/// Two tables of rows; only `columns` may expand into raw var2 entries.
+struct TablesPayload {
+    rows: Vec<Vec<u16>>,
+    id: u8,
+    columns: Vec<Vec<u16>>,
+}
+
+#[test]
+fn derive_nests_non_last_var2_fields() {
+    let value = TablesPayload {
+        rows: vec![vec![1, 2], vec![], vec![3]],
+        id: 9,
+        columns: vec![vec![4], vec![5, 6, 7]],
+    };
+
+    let mut encoder = Encoder::new(Config::builder().big().build());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::builder().big().build()).expect("decoder");
+    let nested = Decoder::new(
+        decoder.peek_var().expect("nested"),
+        Config::builder().big().build(),
+    )
+    .expect("nested decoder");
+    // One entry for the nested `rows` payload plus one per `columns` row.
+    assert_eq!(nested.var_count(), 1 + 2);
+
+    let view = TablesPayload::decode_field::<true>(&mut decoder).expect("view");
+    assert_eq!(view.rows, value.rows);
+    assert_eq!(view.id, value.id);
+    assert_eq!(view.columns, value.columns);
+}
+
+#[derive(Encode, Decode)]
+/// Same layout as `TablesPayload`, with tables decoded into single backing buffers.
+struct FlatTablesPayload {
+    rows: pufu_core::Rows<u16>,
+    id: u8,
+    columns: pufu_core::Rows<u16>,
+}
+
+#[test]
+fn derive_rows_fields_share_the_vec_vec_layout() {
+    let value = TablesPayload {
+        rows: vec![vec![1, 2], vec![], vec![3]],
+        id: 9,
+        columns: vec![vec![4], vec![5, 6, 7]],
+    };
+
+    let mut encoder = Encoder::new(Config::default());
+    value.encode_field::<true>(&mut encoder);
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    let view = FlatTablesPayload::decode_field::<true>(&mut decoder).expect("view");
+    assert!(view.rows.iter().eq(value.rows.iter().map(Vec::as_slice)));
+    assert_eq!(view.id, value.id);
+    assert!(view
+        .columns
+        .iter()
+        .eq(value.columns.iter().map(Vec::as_slice)));
+}
+
+#[derive(Encode, Decode)]
+/// Payload whose `values` must hold exactly `count` elements.
+struct CountedPayload {
+    count: u32,
+    #[pufu(count_from = "count")]
+    values: Vec<u32>,
+}
+
+#[test]
+fn derive_count_from_checks_companion_length() {
+    for (count, expected) in [(3, true), (2, false), (4, false)] {
+        let value = CountedPayload {
+            count,
+            values: vec![10, 20, 30],
+        };
+
+        let mut encoder = Encoder::new(Config::default());
+        value.encode_field::<true>(&mut encoder);
+        let mut out = Vec::new();
+        encoder.finalize(&mut out).expect("finalize");
+
+        let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+        let result = CountedPayload::decode_field::<true>(&mut decoder);
+        if expected {
+            let view = result.expect("view");
+            assert_eq!(view.count, 3);
+            assert_eq!(view.values, value.values);
+        } else {
+            assert!(matches!(
+                result,
+                Err(pufu_core::CodecError::ValidationFailed)
+            ));
+        }
+    }
+}
+
+#[test]
+fn derive_encoded_len_matches_finalized_length() {
+    fn assert_encoded_len<T: EncodeTrait>(value: &T) {
+        for endian in [pufu_core::Endian::Little, pufu_core::Endian::Big] {
+            let mut encoder = Encoder::new(Config::builder().endian(endian).build());
+            value.encode_field::<true>(&mut encoder);
+            let mut out = Vec::new();
+            encoder.finalize(&mut out).expect("finalize");
+            assert_eq!(pufu_core::encoded_len(value, endian), Ok(out.len()));
+        }
+    }
+
+    assert_encoded_len(&TablesPayload {
+        rows: vec![vec![1, 2], vec![]],
+        id: 3,
+        columns: vec![vec![4], vec![5, 6, 7]],
+    });
+    assert_encoded_len(&OuterPayload {
+        version: 1,
+        inner: InnerPayload {
+            tag: 2,
+            data: vec![3, 4, 5],
+        },
+        tail: 6,
+    });
+    assert_encoded_len(&PaddedPayload {
+        id: 1,
+        _reserved: (),
+        flags: 2,
+        name: vec![3, 4],
+    });
+    assert_encoded_len(&QuotePayload {
+        currency: "USD".to_string(),
+        venue: "X",
+        price: 10,
+    });
+    assert_encoded_len(&DigestPayload {
+        id: 1,
+        digest: vec![0; 32],
+        tail: vec![2],
+    });
+    assert_encoded_len(&GridPayload {
+        origin: Point { x: 1, y: 2 },
+        grid: [Point { x: 3, y: 4 }; 4],
+        label: vec![5],
+    });
+    assert_encoded_len(&KindPayload {
+        kind: Kind::B,
+        history: vec![Kind::A, Kind::C],
+    });
+}
@@ -0,0 +1,34 @@
+//! Derive expansion for structs without encoded fields must not trip unused lints.
+
+#![deny(unused)]
+
+use pufu_core::{Config, Decode as DecodeTrait, Decoder, Encode as EncodeTrait, Encoder};
+use pufu_macros::{Decode, Encode};
+
+#[derive(Encode, Decode)]
+/// Struct with no fields.
+struct EmptyPayload {}
+
+#[derive(Encode, Decode)]
+/// Struct whose only field is skipped.
+struct SkippedOnlyPayload {
+    #[pufu(skip)]
+    cache: u32,
+}
+
+#[test]
+fn derive_empty_struct_roundtrips() {
+    let skipped = SkippedOnlyPayload { cache: 5 };
+    let mut encoder = Encoder::new(Config::default());
+    EmptyPayload {}.encode_field::<false>(&mut encoder);
+    skipped.encode_field::<true>(&mut encoder);
+
+    let mut out = Vec::new();
+    encoder.finalize(&mut out).expect("finalize");
+
+    let mut decoder = Decoder::new(&out, Config::default()).expect("decoder");
+    EmptyPayload::decode_field::<false>(&mut decoder).expect("empty");
+    let view = SkippedOnlyPayload::decode_field::<true>(&mut decoder).expect("skipped");
+    assert_eq!(view.cache, 0);
+    assert_ne!(view.cache, skipped.cache);
+}